@@ -0,0 +1,36 @@
+//! Compares `is_regex` and `regex_void` against `regex(..).void()` for discard-only matches.
+//!
+//! Run with `cargo bench --bench is_regex`.
+use std::hint::black_box;
+use std::time::Instant;
+use winnow::error::EmptyError;
+use winnow::prelude::*;
+use winnow_regex::{is_regex, regex, regex_void};
+
+const ITERATIONS: u32 = 200_000;
+
+fn bench(name: &str, mut f: impl FnMut() -> bool) {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        assert!(black_box(f()));
+    }
+    println!("{name:<16} {:?}/iter", start.elapsed() / ITERATIONS);
+}
+
+fn main() {
+    let input = "2024-05-17T12:34:56Z trailing data";
+    let pattern = r"^(\d{4})-(\d{2})-(\d{2})T(\d{2}):(\d{2}):(\d{2})Z";
+
+    let mut validate = is_regex::<_, _, EmptyError>(pattern);
+    bench("is_regex", || validate.parse_peek(black_box(input)).is_ok());
+
+    let mut void = regex::<_, _, EmptyError>(pattern).void();
+    bench("regex().void()", || {
+        void.parse_peek(black_box(input)).is_ok()
+    });
+
+    let mut discard = regex_void::<_, _, EmptyError>(pattern);
+    bench("regex_void", || {
+        discard.parse_peek(black_box(input)).is_ok()
+    });
+}
@@ -0,0 +1,35 @@
+//! Compares group extraction that reuses the capture locations of the parser against
+//! `captures`, which allocates them for every match, on a pattern with 500 groups.
+//!
+//! Run with `cargo bench --bench wide_groups`.
+use std::hint::black_box;
+use std::time::Instant;
+use winnow::error::{ContextError, EmptyError};
+use winnow::prelude::*;
+use winnow_regex::{captures, required_group};
+
+const ITERATIONS: u32 = 20_000;
+
+fn bench(name: &str, mut f: impl FnMut() -> bool) {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        assert!(black_box(f()));
+    }
+    println!("{name:<20} {:?}/iter", start.elapsed() / ITERATIONS);
+}
+
+fn main() {
+    // Mostly optional groups, of which only the first few participate.
+    let pattern = format!(r"^(\w+)=(\d+){}", "(x)?".repeat(498));
+    let input = "key=42 trailing data";
+
+    let mut reused = required_group::<_, _, ContextError>(pattern.as_str(), 2);
+    bench("required_group", || {
+        reused.parse_peek(black_box(input)).is_ok()
+    });
+
+    let mut allocated = captures::<_, _, EmptyError>(pattern.as_str()).map(|caps| caps.range(2));
+    bench("captures().range()", || {
+        allocated.parse_peek(black_box(input)).is_ok()
+    });
+}
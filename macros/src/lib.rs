@@ -0,0 +1,47 @@
+//! Procedural macros for [`winnow-regex`](https://docs.rs/winnow-regex).
+//!
+//! Use them through the `macros` feature of `winnow-regex` rather than depending on this crate
+//! directly.
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{LitStr, parse_macro_input};
+
+/// Creates a `winnow_regex::regex` parser from a pattern checked at compile time.
+///
+/// The argument must be a string literal. It is parsed during macro expansion, so a syntax
+/// error fails the build with the regex error message instead of panicking when the parser is
+/// created. The expansion is a plain call to `winnow_regex::regex`, so the pattern is still
+/// compiled at runtime, but that can no longer fail on syntax. Limits that depend on the
+/// compiled size, such as the regex size limit, are only checked at runtime.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+///
+/// fn digits<'i>(s: &mut &'i str) -> ModalResult<&'i str> {
+///     winnow_regex::regex!(r"^\d+").parse_next(s)
+/// }
+///
+/// assert_eq!(digits.parse_peek("42abc"), Ok(("abc", "42")));
+/// ```
+///
+/// An invalid pattern is a compile error:
+///
+/// ```compile_fail
+/// use winnow::prelude::*;
+///
+/// fn digits<'i>(s: &mut &'i str) -> ModalResult<&'i str> {
+///     winnow_regex::regex!(r"^\d+(").parse_next(s)
+/// }
+/// ```
+#[proc_macro]
+pub fn regex(input: TokenStream) -> TokenStream {
+    let pattern = parse_macro_input!(input as LitStr);
+    if let Err(e) = regex_syntax::Parser::new().parse(&pattern.value()) {
+        return syn::Error::new(pattern.span(), format!("invalid regex: {e}"))
+            .to_compile_error()
+            .into();
+    }
+    quote!(::winnow_regex::regex(#pattern)).into()
+}
@@ -0,0 +1,867 @@
+//! A [`Regex`] backend built on [`regex_automata::meta::Regex`].
+//!
+//! The `regex` crate is itself a wrapper around `regex-automata`, so this backend matches the
+//! same syntax with the same linear-time guarantee. Going through the lower-level engine gives
+//! access to search options the `regex` API does not expose, such as bounding how much of the
+//! input a single match attempt may look at.
+use crate::{Error, RegexPattern, consume_match, regex_trait::*, slice_at};
+use core::marker::PhantomData;
+#[cfg(feature = "deadline")]
+use regex_automata::hybrid;
+use regex_automata::{
+    Anchored, Input, MatchKind,
+    dfa::{Automaton, OverlappingState, StartKind, dense},
+    meta,
+    util::{captures::Captures, primitives::NonMaxUsize},
+};
+use std::sync::Arc;
+#[cfg(feature = "deadline")]
+use std::time::{Duration, Instant};
+#[cfg(feature = "deadline")]
+use winnow::error::FromExternalError;
+use winnow::{
+    Parser,
+    error::{ContextError, ErrMode, Needed, ParserError},
+    stream::{Offset, Stream, StreamIsPartial},
+};
+
+/// A compiled `regex-automata` regex usable with every parser in this crate.
+///
+/// `H` selects the haystack the regex is matched against: `[u8]` (the default) for byte
+/// streams or `str` for string streams, e.g. `AutomataRegex::<str>::new(r"^\w+")`.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::{automata::AutomataRegex, regex};
+///
+/// fn digits<'i>(s: &mut &'i [u8]) -> ModalResult<&'i [u8]> {
+///     regex(AutomataRegex::<[u8]>::new(r"^\d+").unwrap().budget(8)).parse_next(s)
+/// }
+///
+/// assert_eq!(digits.parse_peek(b"123 abc"), Ok((&b" abc"[..], &b"123"[..])));
+/// // The match would need more than 8 bytes, so it fails instead.
+/// assert!(digits.parse_peek(b"1234567890").is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct AutomataRegex<H: ?Sized = [u8]> {
+    re: meta::Regex,
+    /// `None` for a regex converted from a [`meta::Regex`].
+    pattern: Option<Arc<str>>,
+    budget: Option<usize>,
+    anchored: Anchored,
+    earliest: bool,
+    /// End of the search window set by [`regex_automata_with`], relative to the remaining input.
+    limit: Option<usize>,
+    _marker: PhantomData<fn(&H)>,
+}
+
+impl<H: ?Sized> AutomataRegex<H> {
+    /// Compiles `pattern` with the default `regex-automata` configuration.
+    pub fn new(pattern: &str) -> Result<Self, Error> {
+        Ok(Self {
+            pattern: Some(pattern.into()),
+            ..Self::from(meta::Regex::new(pattern).map_err(Box::new)?)
+        })
+    }
+
+    /// Limits every match attempt to the first `budget` bytes of the remaining input.
+    ///
+    /// When the input is longer than the budget, the search only runs over that prefix. If it
+    /// finds no match there, or the match reaches the end of the prefix and so might have
+    /// continued, the budget counts as exceeded and the regex reports no match, which makes the
+    /// parser fail. On partial input this surfaces as `Incomplete` like any other missing
+    /// match, so the budget is best used with complete input.
+    ///
+    /// This is an absolute bound on the work done per match, not a guard against catastrophic
+    /// backtracking: the engine already runs in time linear in the input it is given. Note that
+    /// a match found well inside the budget is the preferred match *within* the prefix, which
+    /// can differ from the unbounded result when a higher-priority alternative would have
+    /// needed more bytes.
+    pub fn budget(mut self, budget: usize) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Returns the underlying `regex-automata` regex.
+    pub fn as_meta(&self) -> &meta::Regex {
+        &self.re
+    }
+
+    /// Applies the anchoring and earliest-match flag of `config` to every search, and stops
+    /// every search `limit` bytes after the cursor, if given.
+    ///
+    /// See [`regex_automata_with`].
+    pub fn input_config(mut self, config: &Input<'_>, limit: Option<usize>) -> Self {
+        self.anchored = config.get_anchored();
+        self.earliest = config.get_earliest();
+        self.limit = limit;
+        self
+    }
+
+    /// Returns the end of the search window in `haystack`, before the budget is applied.
+    fn window_end(&self, len: usize) -> usize {
+        self.limit.map_or(len, |limit| limit.min(len))
+    }
+
+    /// Returns the input to search within `haystack` from `at`, bounded by the budget.
+    fn input<'h>(&self, haystack: &'h [u8], at: usize) -> Input<'h> {
+        let window_end = self.window_end(haystack.len());
+        let end = self.budget.map_or(window_end, |budget| {
+            budget.saturating_add(at).min(window_end)
+        });
+        let start = at.min(end);
+        Input::new(haystack)
+            .span(start..end)
+            .anchored(self.anchored)
+            .earliest(self.earliest)
+    }
+
+    /// Rejects a match that reaches the end of a search window cut short by the budget.
+    fn within_budget(&self, input: &Input<'_>, m: regex_automata::Match) -> Option<(usize, usize)> {
+        if input.end() < self.window_end(input.haystack().len()) && m.end() == input.end() {
+            return None;
+        }
+        Some((m.start(), m.end()))
+    }
+
+    fn search(
+        &self,
+        locs: &mut AutomataCaptures<H>,
+        haystack: &[u8],
+        at: usize,
+    ) -> Option<(usize, usize)> {
+        let input = self.input(haystack, at);
+        self.re.search_captures(&input, &mut locs.caps);
+
+        let found = self.within_budget(&input, locs.caps.get_match()?);
+        if found.is_none() {
+            locs.caps.set_pattern(None);
+        }
+        found
+    }
+
+    fn find_in(&self, haystack: &[u8]) -> Option<(usize, usize)> {
+        let input = self.input(haystack, 0);
+        self.within_budget(&input, self.re.search(&input)?)
+    }
+
+    fn is_match_in(&self, haystack: &[u8], at: usize) -> bool {
+        self.re.is_match(self.input(haystack, at))
+    }
+}
+
+/// The pattern of a regex built this way is unknown, so [`Regex::as_str`] returns an empty
+/// string.
+impl<H: ?Sized> From<meta::Regex> for AutomataRegex<H> {
+    fn from(re: meta::Regex) -> Self {
+        Self {
+            re,
+            pattern: None,
+            budget: None,
+            anchored: Anchored::No,
+            earliest: false,
+            limit: None,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<H: ?Sized> RegexPattern for AutomataRegex<H> {
+    type Error = Error;
+    type Output = Self;
+
+    #[inline(always)]
+    fn try_into_regex(self) -> Result<Self::Output, Self::Error> {
+        Ok(self)
+    }
+}
+
+/// Capture locations of an [`AutomataRegex`] match.
+#[derive(Debug, Clone)]
+pub struct AutomataCaptures<H: ?Sized> {
+    caps: Captures,
+    _marker: PhantomData<fn(&H)>,
+}
+
+impl<H: ?Sized> CaptureLocations for AutomataCaptures<H> {
+    type Input = H;
+
+    #[inline]
+    fn get(&self, i: usize) -> Option<(usize, usize)> {
+        self.caps.get_group(i).map(|span| (span.start, span.end))
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.caps.group_len()
+    }
+}
+
+macro_rules! impl_regex {
+    ($hay:ty, $as_bytes:expr) => {
+        impl Regex for AutomataRegex<$hay> {
+            type Haystack<'h> = &'h $hay;
+            type CaptureLocations = AutomataCaptures<$hay>;
+
+            #[inline]
+            fn capture_locations(&self) -> Self::CaptureLocations {
+                AutomataCaptures {
+                    caps: self.re.create_captures(),
+                    _marker: PhantomData,
+                }
+            }
+
+            #[inline]
+            fn as_str(&self) -> &str {
+                self.pattern.as_deref().unwrap_or_default()
+            }
+
+            #[inline]
+            fn capture_names(&self) -> impl Iterator<Item = Option<&str>> {
+                self.re
+                    .group_info()
+                    .pattern_names(regex_automata::PatternID::ZERO)
+            }
+
+            #[inline]
+            fn captures_read(
+                &self,
+                locs: &mut Self::CaptureLocations,
+                haystack: Self::Haystack<'_>,
+            ) -> Option<(usize, usize)> {
+                let as_bytes: fn(&$hay) -> &[u8] = $as_bytes;
+                self.search(locs, as_bytes(haystack), 0)
+            }
+
+            #[inline]
+            fn captures_read_at(
+                &self,
+                locs: &mut Self::CaptureLocations,
+                haystack: Self::Haystack<'_>,
+                at: usize,
+            ) -> Option<(usize, usize)> {
+                let as_bytes: fn(&$hay) -> &[u8] = $as_bytes;
+                self.search(locs, as_bytes(haystack), at)
+            }
+
+            #[inline]
+            fn find(&self, haystack: Self::Haystack<'_>) -> Option<(usize, usize)> {
+                let as_bytes: fn(&$hay) -> &[u8] = $as_bytes;
+                self.find_in(as_bytes(haystack))
+            }
+
+            #[inline]
+            fn is_match_at(&self, haystack: Self::Haystack<'_>, at: usize) -> bool {
+                let as_bytes: fn(&$hay) -> &[u8] = $as_bytes;
+                self.is_match_in(as_bytes(haystack), at)
+            }
+        }
+    };
+}
+
+impl_regex!([u8], |hay| hay);
+impl_regex!(str, str::as_bytes);
+
+pub struct SlotsParser<'h, I, H, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    H: ?Sized,
+    E: ParserError<I>,
+{
+    re: AutomataRegex<H>,
+    groups: Vec<usize>,
+    _marker: PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, H, E> Parser<I, Vec<Option<<I as Stream>::Slice>>, E> for SlotsParser<'h, I, H, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    <I as Stream>::Slice: AsRef<[u8]>,
+    H: ?Sized,
+    AutomataRegex<H>: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<Vec<Option<<I as Stream>::Slice>>, E> {
+        // Only the slots up to the highest requested group are searched for.
+        let group_len = self
+            .re
+            .re
+            .group_info()
+            .group_len(regex_automata::PatternID::ZERO);
+        let len = self
+            .groups
+            .iter()
+            .max()
+            .map_or(1, |&max| (max + 1).clamp(1, group_len));
+        let mut slots = vec![None; len * 2];
+        let found = self.search_slots(input.peek_finish().as_ref(), &mut slots);
+
+        let start = input.checkpoint();
+        if <I as StreamIsPartial>::is_partial_supported() {
+            consume_match::<_, _, E, true>(input, found)
+        } else {
+            consume_match::<_, _, E, false>(input, found)
+        }?;
+        Ok(self
+            .groups
+            .iter()
+            .map(|&i| match slots.get(i * 2..i * 2 + 2) {
+                Some(&[Some(start_slot), Some(end_slot)]) => {
+                    Some(slice_at(input, &start, (start_slot.get(), end_slot.get())))
+                }
+                _ => None,
+            })
+            .collect())
+    }
+}
+
+impl<I, H, E> SlotsParser<'_, I, H, E>
+where
+    I: Stream,
+    H: ?Sized,
+    E: ParserError<I>,
+{
+    /// Searches with the given slots, returning the end of a match starting at offset 0.
+    fn search_slots(
+        &self,
+        haystack: &[u8],
+        slots: &mut [Option<NonMaxUsize>],
+    ) -> Option<(usize, ())> {
+        let input = self.re.input(haystack, 0);
+        self.re.re.search_slots(&input, slots)?;
+        let (start, end) = (slots[0]?.get(), slots[1]?.get());
+        let m = regex_automata::Match::must(0, start..end);
+        match self.re.within_budget(&input, m) {
+            Some((0, end)) => Some((end, ())),
+            _ => None,
+        }
+    }
+}
+
+/// Creates a parser that matches `re` and returns only the listed capture groups.
+///
+/// The result holds the text of each group in `groups`, in the order given, or `None` for a
+/// group that did not participate or does not exist. Group 0 is the whole match.
+///
+/// The search only resolves the capture slots up to the highest listed group, using the slot
+/// configuration of the `regex-automata` engine, which is why this is tied to
+/// [`AutomataRegex`]. Slots are filled in pattern order, so a pattern with many groups is
+/// cheapest to query when the interesting groups come first; asking for group 0 alone lets the
+/// engine skip capture tracking entirely. The budget of `re` is honoured like in every other
+/// parser.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::automata::{AutomataRegex, captures_slots};
+///
+/// fn first_two<'i>(s: &mut &'i str) -> ModalResult<Vec<Option<&'i str>>> {
+///     let re = AutomataRegex::<str>::new(r"^(\w+),(\w+),(\w+),(\w+)").unwrap();
+///     captures_slots(re, &[2, 1]).parse_next(s)
+/// }
+///
+/// assert_eq!(first_two.parse_peek("a,b,c,d;"), Ok((";", vec![Some("b"), Some("a")])));
+/// ```
+#[inline(always)]
+pub fn captures_slots<'h, Input, H, Error>(
+    re: AutomataRegex<H>,
+    groups: &[usize],
+) -> SlotsParser<'h, Input, H, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    H: ?Sized,
+    AutomataRegex<H>: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Error: ParserError<Input> + 'static,
+{
+    SlotsParser {
+        re,
+        groups: groups.to_vec(),
+        _marker: PhantomData,
+    }
+}
+
+/// Creates a [`regex`](crate::regex()) parser whose searches use the anchoring and
+/// earliest-match flag of a `regex_automata` [`Input`], and stop `limit` bytes after the cursor.
+///
+/// This is the low-level escape hatch for the `regex-automata` backend: `config` serves as a
+/// template whose haystack and span are ignored, and its settings are applied to every search.
+///
+/// - [`Anchored::Yes`] anchors the search at winnow's cursor, so the pattern needs no `^`.
+/// - [`Input::earliest`] stops at the first match end the engine sees, e.g. one digit for
+///   `\d+`, trading leftmost-first semantics for speed.
+/// - `limit` bounds how far a match may reach, in bytes from winnow's cursor; `None` searches
+///   to the end of the input. Unlike [`AutomataRegex::budget`], a match ending exactly at the
+///   limit is accepted.
+///
+/// On partial input the rules of [`regex`](crate::regex()) apply to whatever the search returns.
+///
+/// # Example
+///
+/// ```
+/// use regex_automata::{Anchored, Input};
+/// use winnow::prelude::*;
+/// use winnow_regex::automata::{AutomataRegex, regex_automata_with};
+///
+/// // Anchored without `^`, and only looking at the first 4 bytes.
+/// let config = Input::new("").anchored(Anchored::Yes);
+/// let re = AutomataRegex::<str>::new(r"\d+").unwrap();
+/// let mut digits = regex_automata_with::<_, _, winnow::error::ContextError>(re, &config, Some(4));
+///
+/// assert_eq!(digits.parse_peek("123456"), Ok(("56", "1234")));
+/// assert!(digits.parse_peek("x1").is_err());
+/// ```
+#[inline(always)]
+pub fn regex_automata_with<'h, Input, H, Error>(
+    re: AutomataRegex<H>,
+    config: &regex_automata::Input<'_>,
+    limit: Option<usize>,
+) -> crate::RegexParser<'h, Input, AutomataRegex<H>, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    H: ?Sized,
+    AutomataRegex<H>: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Error: ParserError<Input> + 'static,
+{
+    crate::regex(re.input_config(config, limit))
+}
+
+/// The error attached by [`regex_deadline`] when a match attempt runs past its deadline.
+#[cfg(feature = "deadline")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("match attempt exceeded its deadline")]
+pub struct DeadlineExceeded;
+
+/// How many bytes [`DeadlineParser`] steps through between reads of the clock.
+#[cfg(feature = "deadline")]
+const CLOCK_INTERVAL: usize = 4 * 1024;
+
+#[cfg(feature = "deadline")]
+pub struct DeadlineParser<'h, I, H, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    H: ?Sized,
+    E: ParserError<I>,
+{
+    re: AutomataRegex<H>,
+    /// `None` when the pattern of `re` is unknown.
+    lazy: Option<(hybrid::dfa::DFA, hybrid::dfa::Cache)>,
+    deadline: Duration,
+    _marker: PhantomData<(&'h (), I, E)>,
+}
+
+#[cfg(feature = "deadline")]
+impl<I, H, E> DeadlineParser<'_, I, H, E>
+where
+    I: Stream,
+    H: ?Sized,
+    E: ParserError<I>,
+{
+    /// Returns the end of the leftmost-first match in `input`, stepping the lazy DFA and reading
+    /// the clock every [`CLOCK_INTERVAL`] bytes.
+    ///
+    /// Where the lazy DFA gives up, on a non-ASCII byte next to a Unicode word boundary, the
+    /// attempt finishes with an unchecked search, as does every attempt without a lazy DFA.
+    fn match_end(&mut self, input: &Input<'_>) -> Result<Option<usize>, DeadlineExceeded> {
+        let started = Instant::now();
+        let haystack = input.haystack();
+        let unchecked = |re: &meta::Regex| Ok(re.search(input).map(|m| m.end()));
+        let Some((dfa, cache)) = self.lazy.as_mut() else {
+            return unchecked(&self.re.re);
+        };
+        let Ok(mut sid) = dfa.start_state_forward(cache, input) else {
+            return unchecked(&self.re.re);
+        };
+        let mut end = None;
+        let mut at = input.start();
+        let mut check = at.saturating_add(CLOCK_INTERVAL);
+        while at < input.end() {
+            if at == check {
+                if started.elapsed() > self.deadline {
+                    return Err(DeadlineExceeded);
+                }
+                check = at.saturating_add(CLOCK_INTERVAL);
+            }
+            sid = match dfa.next_state(cache, sid, haystack[at]) {
+                Ok(sid) if !sid.is_quit() => sid,
+                _ => return unchecked(&self.re.re),
+            };
+            // Match states are entered one byte after the match ends.
+            if sid.is_match() {
+                end = Some(at);
+                if input.get_earliest() {
+                    return Ok(end);
+                }
+            } else if sid.is_dead() {
+                return Ok(end);
+            }
+            at += 1;
+        }
+        // Look-around at the end of the span still sees the byte after it.
+        let last = match haystack.get(at) {
+            Some(&byte) => dfa.next_state(cache, sid, byte),
+            None => dfa.next_eoi_state(cache, sid),
+        };
+        match last {
+            Ok(sid) if sid.is_quit() => unchecked(&self.re.re),
+            Ok(sid) if sid.is_match() => Ok(Some(at)),
+            Ok(_) => Ok(end),
+            Err(_) => unchecked(&self.re.re),
+        }
+    }
+}
+
+#[cfg(feature = "deadline")]
+impl<'h, I, H, E> Parser<I, <I as Stream>::Slice, E> for DeadlineParser<'h, I, H, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    <I as Stream>::Slice: AsRef<[u8]>,
+    H: ?Sized,
+    AutomataRegex<H>: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I> + FromExternalError<I, DeadlineExceeded>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<<I as Stream>::Slice, E> {
+        let haystack = input.peek_finish();
+        let search = self.re.input(haystack.as_ref(), 0);
+        let end = match self.match_end(&search) {
+            Ok(end) => end,
+            Err(expired) => return Err(E::from_external_error(input, expired)),
+        };
+        // No match ending by `end` is preferred over the one ending there, so a search that
+        // stops at `end` finds the same match, now with its start.
+        let found = end
+            .and_then(|end| self.re.re.search(&search.clone().span(search.start()..end)))
+            .and_then(|m| self.re.within_budget(&search, m))
+            .and_then(|(start, end)| (start == 0).then_some((end, ())));
+
+        let (slice, ()) = if <I as StreamIsPartial>::is_partial_supported() {
+            consume_match::<_, _, E, true>(input, found)
+        } else {
+            consume_match::<_, _, E, false>(input, found)
+        }?;
+        Ok(slice)
+    }
+}
+
+/// Creates a [`regex`](crate::regex()) parser that gives up on a match attempt once it has run
+/// for longer than `deadline`, failing with [`DeadlineExceeded`] through [`FromExternalError`].
+///
+/// The search steps a lazy DFA through the input and reads the clock every 4 KiB, so it finds
+/// the same match as an unbounded search and overruns the deadline by at most the time it
+/// takes to scan 4 KiB. Once the end of the match is known, a second search that stops there
+/// finds its start. The budget, search limit and anchoring of `re` apply as in every other
+/// parser. A Unicode word boundary `\b` makes the lazy DFA give up on non-ASCII input, and such
+/// an attempt then finishes without reading the clock. The pattern of a regex converted from a
+/// [`meta::Regex`] is unknown, so no lazy DFA can be built for it and every attempt runs
+/// without a deadline.
+///
+/// The default engine already runs in linear time, so this is a safety net against huge inputs
+/// rather than pathological patterns. On partial input the rules of [`regex`](crate::regex())
+/// apply to the match, while an expired attempt fails like on complete input.
+///
+/// # Panics
+///
+/// Panics if the lazy DFA for the pattern of `re` cannot be built.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use winnow::prelude::*;
+/// use winnow_regex::automata::{AutomataRegex, regex_deadline};
+///
+/// let re = AutomataRegex::<str>::new(r"^\w+").unwrap();
+/// let mut word = regex_deadline::<_, _, winnow::error::ContextError>(re, Duration::from_millis(50));
+/// assert_eq!(word.parse_peek("hello world"), Ok((" world", "hello")));
+/// ```
+#[cfg(feature = "deadline")]
+#[inline(always)]
+pub fn regex_deadline<'h, Input, H, Error>(
+    re: AutomataRegex<H>,
+    deadline: Duration,
+) -> DeadlineParser<'h, Input, H, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    H: ?Sized,
+    AutomataRegex<H>: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Error: ParserError<Input> + FromExternalError<Input, DeadlineExceeded> + 'static,
+{
+    let lazy = re.pattern.as_deref().map(|pattern| {
+        let dfa = hybrid::dfa::DFA::builder()
+            .configure(hybrid::dfa::DFA::config().unicode_word_boundary(true))
+            .build(pattern)
+            .unwrap_or_else(|e| panic!("failed to compile regex for deadline parser: {:?}", e));
+        let cache = dfa.create_cache();
+        (dfa, cache)
+    });
+    DeadlineParser {
+        lazy,
+        re,
+        deadline,
+        _marker: PhantomData,
+    }
+}
+
+pub struct OverlappingParser<I, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    E: ParserError<I>,
+{
+    dfa: dense::DFA<Vec<u32>>,
+    _marker: PhantomData<(I, E)>,
+}
+
+impl<I, E> Parser<I, Vec<(usize, usize)>, E> for OverlappingParser<I, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    <I as Stream>::Slice: AsRef<[u8]>,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<Vec<(usize, usize)>, E> {
+        let hay = input.peek_finish();
+        let search = Input::new(hay.as_ref()).anchored(Anchored::Yes);
+        let mut ends: Vec<Option<usize>> = vec![None; self.dfa.pattern_len()];
+        let mut state = OverlappingState::start();
+        while let Ok(()) = self.dfa.try_search_overlapping_fwd(&search, &mut state) {
+            let Some(m) = state.get_match() else { break };
+            let end = &mut ends[m.pattern().as_usize()];
+            *end = Some(end.map_or(m.offset(), |end| end.max(m.offset())));
+        }
+        let found: Vec<_> = ends
+            .into_iter()
+            .enumerate()
+            .filter_map(|(id, end)| Some((id, end?)))
+            .collect();
+
+        let partial = <I as StreamIsPartial>::is_partial_supported() && input.is_partial();
+        if partial && (found.is_empty() || found.iter().any(|&(_, end)| end == input.eof_offset()))
+        {
+            Err(E::incomplete(input, Needed::Unknown))
+        } else if found.is_empty() {
+            Err(ParserError::from_input(input))
+        } else {
+            Ok(found)
+        }
+    }
+}
+
+/// Creates a parser that reports every pattern of a set that matches at the current position,
+/// with the end of its longest match there.
+///
+/// This is an advanced, automata-only tool for ambiguous lexers that want to see all
+/// candidates before deciding, e.g. a keyword that is also a valid identifier. The patterns are
+/// compiled together into a dense DFA with `regex-automata`'s overlapping search, so they are
+/// all tried in a single pass over the input. Each pattern matches at the current position
+/// only, so a leading `^` is not needed.
+///
+/// The result lists `(pattern_index, end)` pairs in pattern order, for the patterns that
+/// matched. Nothing is consumed: follow up with a parser that takes the chosen token, such as
+/// `take(end)`. If no pattern matches, the parser fails. On partial input, no match or any
+/// match reaching the end of the buffer returns `Incomplete`, since more data could extend it.
+///
+/// A dense DFA does not support Unicode word boundaries (`\b`); use `(?-u:\b)` or leave them
+/// out.
+///
+/// # Panics
+///
+/// Panics if a pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow::token::take;
+/// use winnow_regex::automata::overlapping_matches;
+///
+/// const KEYWORD: usize = 0;
+/// const IDENT: usize = 1;
+///
+/// fn token<'i>(s: &mut &'i str) -> ModalResult<(usize, &'i str)> {
+///     let found = overlapping_matches(&["if|else", r"[a-z]+"]).parse_next(s)?;
+///     // Prefer the longest candidate, and a keyword over an identifier of the same length.
+///     let (kind, end) = found
+///         .into_iter()
+///         .max_by_key(|&(kind, end)| (end, kind == KEYWORD))
+///         .unwrap();
+///     take(end).map(|text| (kind, text)).parse_next(s)
+/// }
+///
+/// assert_eq!(token.parse_peek("if x"), Ok((" x", (KEYWORD, "if"))));
+/// assert_eq!(token.parse_peek("iffy x"), Ok((" x", (IDENT, "iffy"))));
+/// ```
+#[inline(always)]
+pub fn overlapping_matches<Input, Error>(patterns: &[&str]) -> OverlappingParser<Input, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Error: ParserError<Input> + 'static,
+{
+    let dfa = dense::Builder::new()
+        .configure(
+            dense::Config::new()
+                .match_kind(MatchKind::All)
+                .start_kind(StartKind::Anchored),
+        )
+        .build_many(patterns)
+        .unwrap_or_else(|e| panic!("failed to compile regex for overlapping parser: {:?}", e));
+
+    OverlappingParser {
+        dfa,
+        _marker: PhantomData,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{captures, captures_at, regex};
+    use winnow::error::EmptyError;
+
+    #[test]
+    fn str_captures() {
+        let mut s = "12x34 rest";
+        let re = AutomataRegex::<str>::new(r"^(?<w>\d+)x(\d+)").unwrap();
+        let caps = captures::<_, _, EmptyError>(re).parse_next(&mut s).unwrap();
+        assert_eq!((&caps[0], &caps[1], &caps[2]), ("12x34", "12", "34"));
+        assert_eq!(caps.named_iter().collect::<Vec<_>>(), [("w", Some("12"))]);
+        assert_eq!(s, " rest");
+    }
+
+    #[test]
+    fn parser_capture_names() {
+        let re = AutomataRegex::<str>::new(r"^(?<k>\w+)=(\w+)").unwrap();
+        let p = regex::<&str, _, EmptyError>(re);
+        assert_eq!(
+            p.capture_names().collect::<Vec<_>>(),
+            [None, Some("k"), None]
+        );
+    }
+
+    #[test]
+    fn budget() {
+        let re = AutomataRegex::<str>::new(r"^\w+").unwrap().budget(4);
+        let mut p = regex::<_, _, EmptyError>(re);
+        assert_eq!(p.parse_peek("abc def"), Ok((" def", "abc")));
+        assert_eq!(p.parse_peek("abcd"), Ok(("", "abcd")));
+        assert!(p.parse_peek("abcde").is_err());
+    }
+
+    #[cfg(feature = "deadline")]
+    #[test]
+    fn deadline() {
+        use std::time::Duration;
+        use winnow::error::ContextError;
+        use winnow::stream::Partial;
+
+        let large = "a".repeat(1 << 20);
+        let re = || AutomataRegex::<str>::new(r"^a+").unwrap();
+        let mut p = regex_deadline::<_, _, ContextError>(re(), Duration::ZERO);
+        let err = p.parse_peek(large.as_str()).unwrap_err();
+        assert_eq!(
+            err.cause().and_then(|cause| cause.downcast_ref()),
+            Some(&DeadlineExceeded)
+        );
+        // The clock is first read after 4 KiB.
+        assert_eq!(p.parse_peek("aab"), Ok(("b", "aa")));
+
+        // An expired attempt is not taken for a match that needs more input.
+        let mut p = regex_deadline::<_, _, ErrMode<ContextError>>(re(), Duration::ZERO);
+        assert!(matches!(
+            p.parse_peek(Partial::new(large.as_str())),
+            Err(ErrMode::Backtrack(_))
+        ));
+
+        let mut p = regex_deadline::<_, _, ContextError>(re(), Duration::from_secs(3600));
+        assert_eq!(p.parse_peek(large.as_str()), Ok(("", large.as_str())));
+
+        // The preferred alternative wins even when it needs more than the first 4 KiB.
+        let long = format!("{}b", "a".repeat(5000));
+        let re = AutomataRegex::<str>::new(r"^(?:a*b|a)").unwrap();
+        let mut p = regex_deadline::<_, _, ContextError>(re, Duration::from_secs(3600));
+        assert_eq!(p.parse_peek(long.as_str()), Ok(("", long.as_str())));
+
+        let re = AutomataRegex::<str>::new(r"\d+").unwrap();
+        let mut p = regex_deadline::<_, _, ContextError>(re, Duration::from_secs(3600));
+        assert_eq!(p.parse_peek("12ab"), Ok(("ab", "12")));
+        assert!(p.parse_peek("ab12").is_err());
+        // The lazy DFA gives up on a Unicode word boundary next to `é`.
+        let re = AutomataRegex::<str>::new(r"^\w+\b").unwrap();
+        let mut p = regex_deadline::<_, _, ContextError>(re, Duration::from_secs(3600));
+        assert_eq!(p.parse_peek("héllo wörld"), Ok((" wörld", "héllo")));
+        // Without a known pattern every attempt runs unchecked.
+        let digits = "1".repeat(1 << 20);
+        let re = AutomataRegex::<str>::from(meta::Regex::new(r"^\d+").unwrap());
+        let mut p = regex_deadline::<_, _, ContextError>(re, Duration::ZERO);
+        assert_eq!(p.parse_peek(digits.as_str()), Ok(("", digits.as_str())));
+        assert!(p.parse_peek("ab12").is_err());
+    }
+
+    #[test]
+    fn captures_at_offset() {
+        let re = AutomataRegex::<[u8]>::new(r"(\d+)").unwrap();
+        let mut p = captures_at::<_, _, EmptyError>(re, 2);
+        let (rest, caps) = p.parse_peek(&b"x=42;"[..]).unwrap();
+        assert_eq!((rest, &caps[1]), (&b";"[..], &b"42"[..]));
+        assert_eq!(caps.range(1), Some(0..2));
+    }
+
+    #[test]
+    fn captures_slots_subset() {
+        let re = AutomataRegex::<[u8]>::new(r"^(\d+)(?:x(\d+))?(px)?").unwrap();
+        let mut p = captures_slots::<_, _, EmptyError>(re, &[0, 2, 9]);
+        assert_eq!(
+            p.parse_peek(&b"80x60;"[..]),
+            Ok((&b";"[..], vec![Some(&b"80x60"[..]), Some(&b"60"[..]), None]))
+        );
+        assert_eq!(
+            p.parse_peek(&b"80;"[..]),
+            Ok((&b";"[..], vec![Some(&b"80"[..]), None, None]))
+        );
+        assert!(p.parse_peek(&b";"[..]).is_err());
+    }
+
+    #[test]
+    fn input_config() {
+        let config = Input::new("").anchored(Anchored::Yes).earliest(true);
+        let re = AutomataRegex::<str>::new(r"\d+").unwrap();
+        let mut p = regex_automata_with::<_, _, EmptyError>(re, &config, None);
+        assert_eq!(p.parse_peek("123"), Ok(("23", "1")));
+        assert!(p.parse_peek("a1").is_err());
+
+        // The window end composes with the budget; only the budget rejects a match at its edge.
+        let config = Input::new("");
+        let re = AutomataRegex::<[u8]>::new(r"^\w+").unwrap().budget(2);
+        let mut p = regex_automata_with::<_, _, EmptyError>(re, &config, Some(3));
+        assert!(p.parse_peek(&b"abcdef"[..]).is_err());
+        let re = AutomataRegex::<[u8]>::new(r"^\w+").unwrap().budget(5);
+        let mut p = regex_automata_with::<_, _, EmptyError>(re, &config, Some(3));
+        assert_eq!(p.parse_peek(&b"abcdef"[..]), Ok((&b"def"[..], &b"abc"[..])));
+
+        // The limit no longer depends on the length of the template's haystack.
+        let config = Input::new(&[0; 3][..]).range(..3);
+        let re = AutomataRegex::<[u8]>::new(r"^\w+").unwrap();
+        let mut p = regex_automata_with::<_, _, EmptyError>(re, &config, Some(3));
+        assert_eq!(p.parse_peek(&b"abcdef"[..]), Ok((&b"def"[..], &b"abc"[..])));
+        let re = AutomataRegex::<[u8]>::new(r"^\w+").unwrap();
+        let mut p = regex_automata_with::<_, _, EmptyError>(re, &config, None);
+        assert_eq!(p.parse_peek(&b"abcdef"[..]), Ok((&b""[..], &b"abcdef"[..])));
+    }
+
+    #[test]
+    fn overlapping_all_candidates() {
+        let mut p = overlapping_matches::<_, EmptyError>(&["if", "[a-z]+", "[0-9]+"]);
+        assert_eq!(p.parse_peek("if("), Ok(("if(", vec![(0, 2), (1, 2)])));
+        assert_eq!(p.parse_peek("ifs("), Ok(("ifs(", vec![(0, 2), (1, 3)])));
+        assert!(p.parse_peek("(").is_err());
+
+        use winnow::error::{ContextError, ErrMode};
+        use winnow::stream::Partial;
+        let mut p = overlapping_matches::<_, ErrMode<ContextError>>(&["if", "[a-z]+"]);
+        assert!(
+            p.parse_peek(Partial::new("if"))
+                .unwrap_err()
+                .is_incomplete()
+        );
+    }
+}
@@ -0,0 +1,347 @@
+//! A [`Regex`] whose engine is picked at runtime.
+//!
+//! [`Backend`] wraps one of several regex engines and implements [`Regex`] by dispatching to
+//! it, so a grammar can choose the engine per pattern without boxing the regex or making its
+//! parsers generic over the engine. Only the `regex` crate is always available; the other
+//! engines are behind crate features:
+//!
+//! - [`Engine::Regex`]: the `regex` crate, always available.
+//! - [`Engine::Lite`]: `regex-lite`, smaller and faster to compile but slower to match, with the
+//!   `lite` feature.
+//! - [`Engine::Fancy`]: `fancy-regex`, which adds look-around and backreferences at the cost of
+//!   the linear-time guarantee, with the `fancy` feature.
+use crate::{Error, RegexParser, RegexPattern, regex_trait::*};
+use core::fmt::Debug;
+use winnow::{
+    error::ParserError,
+    stream::{Offset, Stream, StreamIsPartial},
+};
+
+/// Selects the engine [`Backend::new`] compiles a pattern with.
+///
+/// Each variant other than [`Engine::Regex`] exists only with its crate feature enabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Engine {
+    /// The `regex` crate.
+    Regex,
+    /// The `regex-lite` crate.
+    #[cfg(feature = "lite")]
+    Lite,
+    /// The `fancy-regex` crate.
+    #[cfg(feature = "fancy")]
+    Fancy,
+}
+
+/// A compiled regex of the [`Engine`] chosen at runtime.
+///
+/// All engines search `str` haystacks, so a `Backend` works with every parser on string
+/// streams. `fancy-regex` reports a search that hits its backtracking limit as an error; the
+/// [`Regex`] trait has no way to pass it on, so such a search counts as no match.
+///
+/// # Example
+///
+/// A grammar can keep the linear-time `regex` engine for most patterns and fall back to
+/// `fancy-regex` only for those it cannot compile, such as patterns with look-around:
+///
+/// ```
+/// use winnow_regex::backend::{Backend, Engine};
+///
+/// fn compile(pattern: &str) -> Backend {
+///     Backend::new(pattern, Engine::Regex).unwrap_or_else(|_| fancy(pattern))
+/// }
+///
+/// # #[cfg(feature = "fancy")]
+/// fn fancy(pattern: &str) -> Backend {
+///     Backend::new(pattern, Engine::Fancy).unwrap()
+/// }
+/// # #[cfg(not(feature = "fancy"))]
+/// # fn fancy(pattern: &str) -> Backend {
+/// #     panic!("unsupported pattern {pattern}")
+/// # }
+///
+/// assert_eq!(compile(r"^\d+").engine(), Engine::Regex);
+/// # #[cfg(feature = "fancy")]
+/// assert_eq!(compile(r"^\d+(?=px)").engine(), Engine::Fancy);
+/// ```
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum Backend {
+    Regex(regex::Regex),
+    #[cfg(feature = "lite")]
+    Lite(regex_lite::Regex),
+    #[cfg(feature = "fancy")]
+    Fancy(fancy_regex::Regex),
+}
+
+impl Backend {
+    /// Compiles `pattern` with `engine`.
+    pub fn new(pattern: &str, engine: Engine) -> Result<Self, Error> {
+        Ok(match engine {
+            Engine::Regex => Self::Regex(regex::Regex::new(pattern)?),
+            #[cfg(feature = "lite")]
+            Engine::Lite => Self::Lite(regex_lite::Regex::new(pattern)?),
+            #[cfg(feature = "fancy")]
+            Engine::Fancy => Self::Fancy(fancy_regex::Regex::new(pattern).map_err(Box::new)?),
+        })
+    }
+
+    /// Returns the engine the regex was compiled with.
+    pub fn engine(&self) -> Engine {
+        match self {
+            Self::Regex(_) => Engine::Regex,
+            #[cfg(feature = "lite")]
+            Self::Lite(_) => Engine::Lite,
+            #[cfg(feature = "fancy")]
+            Self::Fancy(_) => Engine::Fancy,
+        }
+    }
+}
+
+impl From<regex::Regex> for Backend {
+    fn from(re: regex::Regex) -> Self {
+        Self::Regex(re)
+    }
+}
+
+#[cfg(feature = "lite")]
+impl From<regex_lite::Regex> for Backend {
+    fn from(re: regex_lite::Regex) -> Self {
+        Self::Lite(re)
+    }
+}
+
+#[cfg(feature = "fancy")]
+impl From<fancy_regex::Regex> for Backend {
+    fn from(re: fancy_regex::Regex) -> Self {
+        Self::Fancy(re)
+    }
+}
+
+impl RegexPattern for Backend {
+    type Error = Error;
+    type Output = Self;
+
+    #[inline(always)]
+    fn try_into_regex(self) -> Result<Self::Output, Self::Error> {
+        Ok(self)
+    }
+}
+
+/// The capture locations of a [`Backend`], only valid with the regex that created them.
+#[derive(Debug, Clone)]
+pub struct BackendLocations(Locations);
+
+#[derive(Debug, Clone)]
+enum Locations {
+    Regex(regex::CaptureLocations),
+    #[cfg(feature = "lite")]
+    Lite(regex_lite::CaptureLocations),
+    /// `fancy-regex` has no reusable locations, so the spans are copied out of each match.
+    #[cfg(feature = "fancy")]
+    Fancy(Vec<Option<(usize, usize)>>),
+}
+
+impl CaptureLocations for BackendLocations {
+    type Input = str;
+
+    #[inline]
+    fn get(&self, i: usize) -> Option<(usize, usize)> {
+        match &self.0 {
+            Locations::Regex(locs) => locs.get(i),
+            #[cfg(feature = "lite")]
+            Locations::Lite(locs) => locs.get(i),
+            #[cfg(feature = "fancy")]
+            Locations::Fancy(spans) => spans.get(i).copied().flatten(),
+        }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        match &self.0 {
+            Locations::Regex(locs) => locs.len(),
+            #[cfg(feature = "lite")]
+            Locations::Lite(locs) => locs.len(),
+            #[cfg(feature = "fancy")]
+            Locations::Fancy(spans) => spans.len(),
+        }
+    }
+}
+
+impl Regex for Backend {
+    type Haystack<'h> = &'h str;
+    type CaptureLocations = BackendLocations;
+
+    fn capture_locations(&self) -> Self::CaptureLocations {
+        BackendLocations(match self {
+            Self::Regex(re) => Locations::Regex(re.capture_locations()),
+            #[cfg(feature = "lite")]
+            Self::Lite(re) => Locations::Lite(re.capture_locations()),
+            #[cfg(feature = "fancy")]
+            Self::Fancy(re) => Locations::Fancy(vec![None; re.captures_len()]),
+        })
+    }
+
+    #[inline]
+    fn as_str(&self) -> &str {
+        match self {
+            Self::Regex(re) => re.as_str(),
+            #[cfg(feature = "lite")]
+            Self::Lite(re) => re.as_str(),
+            #[cfg(feature = "fancy")]
+            Self::Fancy(re) => re.as_str(),
+        }
+    }
+
+    fn capture_names(&self) -> impl Iterator<Item = Option<&str>> {
+        let names: Box<dyn Iterator<Item = Option<&str>>> = match self {
+            Self::Regex(re) => Box::new(re.capture_names()),
+            #[cfg(feature = "lite")]
+            Self::Lite(re) => Box::new(re.capture_names()),
+            #[cfg(feature = "fancy")]
+            Self::Fancy(re) => Box::new(re.capture_names()),
+        };
+        names
+    }
+
+    #[inline]
+    fn captures_read(
+        &self,
+        locs: &mut Self::CaptureLocations,
+        haystack: Self::Haystack<'_>,
+    ) -> Option<(usize, usize)> {
+        self.captures_read_at(locs, haystack, 0)
+    }
+
+    fn captures_read_at(
+        &self,
+        locs: &mut Self::CaptureLocations,
+        haystack: Self::Haystack<'_>,
+        at: usize,
+    ) -> Option<(usize, usize)> {
+        match (self, &mut locs.0) {
+            (Self::Regex(re), Locations::Regex(locs)) => re
+                .captures_read_at(locs, haystack, at)
+                .map(|m| (m.start(), m.end())),
+            #[cfg(feature = "lite")]
+            (Self::Lite(re), Locations::Lite(locs)) => re
+                .captures_read_at(locs, haystack, at)
+                .map(|m| (m.start(), m.end())),
+            #[cfg(feature = "fancy")]
+            (Self::Fancy(re), Locations::Fancy(spans)) => {
+                let caps = re.captures_from_pos(haystack, at).ok().flatten();
+                for (i, span) in spans.iter_mut().enumerate() {
+                    *span = caps
+                        .as_ref()
+                        .and_then(|caps| caps.get(i))
+                        .map(|m| (m.start(), m.end()));
+                }
+                spans[0]
+            }
+            #[allow(unreachable_patterns)]
+            _ => panic!("capture locations were created by a regex of another engine"),
+        }
+    }
+
+    #[inline]
+    fn find(&self, haystack: Self::Haystack<'_>) -> Option<(usize, usize)> {
+        match self {
+            Self::Regex(re) => re.find(haystack).map(|m| (m.start(), m.end())),
+            #[cfg(feature = "lite")]
+            Self::Lite(re) => re.find(haystack).map(|m| (m.start(), m.end())),
+            #[cfg(feature = "fancy")]
+            Self::Fancy(re) => re
+                .find(haystack)
+                .ok()
+                .flatten()
+                .map(|m| (m.start(), m.end())),
+        }
+    }
+
+    #[inline]
+    fn is_match_at(&self, haystack: Self::Haystack<'_>, at: usize) -> bool {
+        match self {
+            Self::Regex(re) => re.is_match_at(haystack, at),
+            #[cfg(feature = "lite")]
+            Self::Lite(re) => re.is_match_at(haystack, at),
+            #[cfg(feature = "fancy")]
+            Self::Fancy(re) => matches!(re.find_from_pos(haystack, at), Ok(Some(_))),
+        }
+    }
+}
+
+/// Creates a parser like [`regex`](crate::regex()) that compiles `pattern` with `engine`.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::backend::{Engine, regex_backend};
+///
+/// fn digits<'i>(s: &mut &'i str) -> ModalResult<&'i str> {
+///     regex_backend(r"^\d+", Engine::Regex).parse_next(s)
+/// }
+///
+/// assert_eq!(digits.parse_peek("42abc"), Ok(("abc", "42")));
+/// ```
+#[inline(always)]
+pub fn regex_backend<'h, Input, Error>(
+    pattern: &str,
+    engine: Engine,
+) -> RegexParser<'h, Input, Backend, Error>
+where
+    Input: StreamIsPartial + Stream<Slice = &'h str> + Offset + Clone,
+    Error: ParserError<Input> + 'static,
+{
+    let re = Backend::new(pattern, engine)
+        .unwrap_or_else(|e| panic!("failed to compile regex for backend parser: {:?}", e));
+    crate::regex(re)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::captures;
+    use winnow::error::EmptyError;
+    use winnow::prelude::*;
+
+    #[test]
+    fn switch_backends() {
+        let engines = [
+            Engine::Regex,
+            #[cfg(feature = "lite")]
+            Engine::Lite,
+            #[cfg(feature = "fancy")]
+            Engine::Fancy,
+        ];
+        for engine in engines {
+            let mut p = regex_backend::<_, EmptyError>(r"^\d+", engine);
+            assert_eq!(p.parse_peek("42abc"), Ok(("abc", "42")), "{engine:?}");
+            assert!(p.parse_peek("abc").is_err(), "{engine:?}");
+
+            let re = Backend::new(r"^(?<w>\d+)x(\d+)?", engine).unwrap();
+            assert_eq!(re.engine(), engine);
+            let caps = captures::<_, _, EmptyError>(re)
+                .parse_next(&mut "800x;")
+                .unwrap();
+            assert_eq!(
+                caps.iter().collect::<Vec<_>>(),
+                [Some("800x"), Some("800"), None]
+            );
+            assert_eq!(caps.name_range("w"), Some(0..3), "{engine:?}");
+        }
+    }
+
+    #[cfg(feature = "fancy")]
+    #[test]
+    fn fancy_lookaround() {
+        assert!(Backend::new(r"^\d+(?=px)", Engine::Regex).is_err());
+        let mut p = regex_backend::<_, EmptyError>(r"^\d+(?=px)", Engine::Fancy);
+        assert_eq!(p.parse_peek("12px"), Ok(("px", "12")));
+        assert!(p.parse_peek("12em").is_err());
+    }
+}
@@ -0,0 +1,119 @@
+//! Configurable regex construction for the parsers in this crate.
+use crate::{Error, RegexPattern, bytes::BytesRegexPattern};
+
+/// A pattern together with the `regex` compile options to build it with.
+///
+/// `RegexBuilder` implements both [`RegexPattern`] and [`BytesRegexPattern`], so it can be
+/// passed anywhere a pattern is accepted and compiles to whichever engine the parser needs.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::{RegexBuilder, regex};
+///
+/// fn lazy_a<'i>(s: &mut &'i str) -> ModalResult<&'i str> {
+///     regex(RegexBuilder::new(r"^a+").swap_greed(true)).parse_next(s)
+/// }
+///
+/// assert_eq!(lazy_a.parse_peek("aaa"), Ok(("aa", "a")));
+/// ```
+#[derive(Debug, Clone)]
+pub struct RegexBuilder {
+    pattern: String,
+    swap_greed: bool,
+    octal: bool,
+    dot_matches_new_line: bool,
+}
+
+impl RegexBuilder {
+    /// Starts a builder for `pattern` with the `regex` crate's default options.
+    pub fn new(pattern: &str) -> Self {
+        Self {
+            pattern: pattern.to_owned(),
+            swap_greed: false,
+            octal: false,
+            dot_matches_new_line: false,
+        }
+    }
+
+    /// Swaps the meaning of greedy and lazy repetition, so `a*` is lazy and `a*?` is greedy.
+    ///
+    /// See [`regex::RegexBuilder::swap_greed`].
+    pub fn swap_greed(mut self, yes: bool) -> Self {
+        self.swap_greed = yes;
+        self
+    }
+
+    /// Allows octal escapes like `\141`, which are rejected by default.
+    ///
+    /// See [`regex::RegexBuilder::octal`].
+    pub fn octal(mut self, yes: bool) -> Self {
+        self.octal = yes;
+        self
+    }
+
+    /// Lets `.` match `\n` as well, like the `s` flag.
+    ///
+    /// See [`regex::RegexBuilder::dot_matches_new_line`].
+    pub fn dot_matches_new_line(mut self, yes: bool) -> Self {
+        self.dot_matches_new_line = yes;
+        self
+    }
+}
+
+macro_rules! build {
+    ($builder:ty, $options:expr) => {{
+        let options = $options;
+        Ok(<$builder>::new(&options.pattern)
+            .swap_greed(options.swap_greed)
+            .octal(options.octal)
+            .dot_matches_new_line(options.dot_matches_new_line)
+            .build()?)
+    }};
+}
+
+impl RegexPattern for RegexBuilder {
+    type Error = Error;
+    type Output = regex::Regex;
+
+    #[inline(always)]
+    fn try_into_regex(self) -> Result<Self::Output, Self::Error> {
+        build!(regex::RegexBuilder, self)
+    }
+}
+
+impl BytesRegexPattern for RegexBuilder {
+    type Error = Error;
+    type Output = regex::bytes::Regex;
+
+    #[inline(always)]
+    fn try_into_regex(self) -> Result<Self::Output, Self::Error> {
+        build!(regex::bytes::RegexBuilder, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use winnow::error::EmptyError;
+    use winnow::prelude::*;
+
+    #[test]
+    fn swap_greed() {
+        let mut greedy = crate::regex::<_, _, EmptyError>(RegexBuilder::new("^a+"));
+        assert_eq!(greedy.parse_peek("aaa"), Ok(("", "aaa")));
+
+        let mut lazy =
+            crate::bytes::regex::<_, _, EmptyError>(RegexBuilder::new("^a+").swap_greed(true));
+        assert_eq!(lazy.parse_peek(&b"aaa"[..]), Ok((&b"aa"[..], &b"a"[..])));
+    }
+
+    #[test]
+    fn octal() {
+        assert!(RegexPattern::try_into_regex(RegexBuilder::new(r"^\141+")).is_err());
+
+        let mut a = crate::regex::<_, _, EmptyError>(RegexBuilder::new(r"^\141+").octal(true));
+        assert_eq!(a.parse_peek("aab"), Ok(("b", "aa")));
+    }
+}
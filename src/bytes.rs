@@ -1,7 +1,13 @@
-use crate::{CapturesParser, Error, Regex, RegexParser};
+use crate::{
+    AnyOfParser, CaptureLocations, CapturesParser, Error, Regex, RegexBuilder, RegexParser,
+    capture_names,
+};
 use core::fmt::Debug;
+use core::str::{FromStr, Utf8Error};
+use regex_automata::hybrid;
 use winnow::{
-    error::ParserError,
+    Parser,
+    error::{ContextError, ErrMode, FromExternalError, Needed, ParserError},
     stream::{Offset, Stream, StreamIsPartial},
 };
 
@@ -37,13 +43,27 @@ impl BytesRegexPattern for &str {
     }
 }
 
-/// A `&[u8]`-oriented version of [`winnow_regex::regex`].
+/// A pattern read as raw bytes, e.g. from a binary config file.
+///
+/// The bytes must be valid UTF-8; otherwise compiling fails with [`Error::Utf8`], and a syntax
+/// error is reported as [`Error::Regex`] like for a `&str` pattern.
+impl BytesRegexPattern for &[u8] {
+    type Error = Error;
+    type Output = regex::bytes::Regex;
+
+    #[inline(always)]
+    fn try_into_regex(self) -> Result<Self::Output, Self::Error> {
+        Ok(Self::Output::new(core::str::from_utf8(self)?)?)
+    }
+}
+
+/// A `&[u8]`-oriented version of [`crate::regex()`].
 ///
 /// This parser matches the beginning of a byte stream (`&[u8]`)
 /// using a regular expression compiled with [`regex::bytes::Regex`].
 /// It returns the matching slice if the regex matches at offset 0.
 ///
-/// For more usage details, see [`winnow_regex::regex`].
+/// For more usage details, see [`crate::regex()`].
 ///
 /// # Panics
 ///
@@ -78,13 +98,83 @@ where
     }
 }
 
-/// A `&[u8]`-oriented version of [`winnow_regex::captures`].
+/// Like [`regex()`], but `.` also matches `\n`.
+///
+/// Binary frames often contain `\n` bytes that should not end a `.` match. This is the same as
+/// writing `(?s)` at the start of the pattern, or building it with
+/// [`RegexBuilder::dot_matches_new_line`].
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::bytes::regex_dotall;
+///
+/// fn payload<'i>(input: &mut &'i [u8]) -> ModalResult<&'i [u8]> {
+///     regex_dotall(r"^.{4}").parse_next(input)
+/// }
+///
+/// assert_eq!(payload.parse_peek(b"a\nb\nc"), Ok((&b"c"[..], &b"a\nb\n"[..])));
+/// ```
+#[inline(always)]
+pub fn regex_dotall<'h, Input, Error>(
+    pattern: &str,
+) -> RegexParser<'h, Input, regex::bytes::Regex, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    regex::bytes::Regex: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Error: ParserError<Input> + 'static,
+{
+    regex(RegexBuilder::new(pattern).dot_matches_new_line(true))
+}
+
+/// A `&[u8]`-oriented version of [`crate::regex_from_builder`], taking a
+/// [`regex::bytes::RegexBuilder`].
+///
+/// # Errors
+///
+/// Returns an error if the regex fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::bytes::regex_from_builder;
+///
+/// fn tag<'i>(input: &mut &'i [u8]) -> ModalResult<&'i [u8]> {
+///     let mut builder = regex::bytes::RegexBuilder::new(r"^\xff\x00");
+///     builder.unicode(false);
+///     regex_from_builder(&builder).unwrap().parse_next(input)
+/// }
+///
+/// assert_eq!(tag.parse_peek(b"\xff\x00rest"), Ok((&b"rest"[..], &b"\xff\x00"[..])));
+/// ```
+#[inline(always)]
+pub fn regex_from_builder<'h, Input, Error>(
+    builder: &regex::bytes::RegexBuilder,
+) -> Result<RegexParser<'h, Input, regex::bytes::Regex, Error>, crate::Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    regex::bytes::Regex: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Error: ParserError<Input> + 'static,
+{
+    Ok(RegexParser {
+        re: builder.build()?,
+        _marker: core::marker::PhantomData,
+    })
+}
+
+/// A `&[u8]`-oriented version of [`crate::captures`].
 ///
 /// This parser matches and extracts capture groups from the beginning of a byte stream (`&[u8]`)
 /// using a regular expression compiled with [`regex::bytes::Regex`].
 /// If the regex matches at offset 0, all capture groups are returned.
 ///
-/// For full semantics and error behavior, see [`winnow_regex::captures`].
+/// For full semantics and error behavior, see [`crate::captures`].
 ///
 /// # Panics
 ///
@@ -120,15 +210,441 @@ where
     let re = re.into_regex();
 
     CapturesParser {
+        names: capture_names(&re),
         re,
         _marker: core::marker::PhantomData,
     }
 }
 
+pub struct AsciiStrParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    inner: RegexParser<'h, I, R, E>,
+}
+
+impl<'h, 'i, I, R, E> Parser<I, &'i str, E> for AsciiStrParser<'h, I, R, E>
+where
+    I: Stream<Slice = &'i [u8]> + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<&'i str, E> {
+        let start = input.checkpoint();
+        let slice = self.inner.parse_next(input)?;
+        if slice.is_ascii() {
+            // ASCII is always valid UTF-8.
+            Ok(core::str::from_utf8(slice).unwrap_or_default())
+        } else {
+            input.reset(&start);
+            Err(ParserError::from_input(input))
+        }
+    }
+}
+
+/// Like [`regex()`], but returns the match as a `&str`, failing if it is not all ASCII.
+///
+/// This suits ASCII tokens such as numbers and keywords in binary protocols, which would
+/// otherwise need a `from_utf8` conversion after matching. A match containing a non-ASCII byte
+/// fails the parse like a non-matching regex, leaving the input untouched.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::bytes::regex_ascii_str;
+///
+/// fn number<'i>(input: &mut &'i [u8]) -> ModalResult<u32> {
+///     regex_ascii_str(r"^\d+").try_map(str::parse).parse_next(input)
+/// }
+///
+/// assert_eq!(number.parse_peek(b"1234\x00"), Ok((&b"\x00"[..], 1234)));
+/// ```
+#[inline(always)]
+pub fn regex_ascii_str<'h, Input, Re, Error>(re: Re) -> AsciiStrParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: BytesRegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    AsciiStrParser { inner: regex(re) }
+}
+
+pub struct FromStrParser<'h, I, R, T, E = ErrMode<ContextError>>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    inner: RegexParser<'h, I, R, E>,
+    _marker: core::marker::PhantomData<T>,
+}
+
+impl<'h, I, R, T, E> Parser<I, T, E> for FromStrParser<'h, I, R, T, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    <I as Stream>::Slice: AsRef<[u8]>,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    T: FromStr,
+    E: ParserError<I> + FromExternalError<I, Utf8Error> + FromExternalError<I, T::Err>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<T, E> {
+        let start = input.checkpoint();
+        let slice = self.inner.parse_next(input)?;
+        match core::str::from_utf8(slice.as_ref()) {
+            Ok(text) => text.parse().map_err(|e| {
+                input.reset(&start);
+                E::from_external_error(input, e)
+            }),
+            Err(e) => {
+                input.reset(&start);
+                Err(E::from_external_error(input, e))
+            }
+        }
+    }
+}
+
+/// Like [`crate::regex_parse`], but for byte streams.
+///
+/// The match is checked to be valid UTF-8 before it is passed to [`FromStr`]; invalid UTF-8 is
+/// reported as a [`Utf8Error`] through [`FromExternalError`], and a `from_str` error likewise.
+/// On any error the input is left untouched.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::bytes::regex_parse;
+///
+/// fn int(input: &mut &[u8]) -> ModalResult<i64> {
+///     regex_parse(r"^-?\d+").parse_next(input)
+/// }
+///
+/// assert_eq!(int.parse_peek(b"-42\x00"), Ok((&b"\x00"[..], -42)));
+/// ```
+#[inline(always)]
+pub fn regex_parse<'h, Input, Re, T, Error>(
+    re: Re,
+) -> FromStrParser<'h, Input, Re::Output, T, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: BytesRegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    T: FromStr,
+    Error: ParserError<Input>
+        + FromExternalError<Input, Utf8Error>
+        + FromExternalError<Input, T::Err>
+        + 'static,
+{
+    FromStrParser {
+        inner: regex(re),
+        _marker: core::marker::PhantomData,
+    }
+}
+
+/// Like [`crate::any_of`], but for precompiled byte regexes.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::bytes::any_of;
+///
+/// let res = [r"^\d+", r"^[a-z]+"].map(|p| regex::bytes::Regex::new(p).unwrap());
+/// let mut token = any_of::<_, winnow::error::ContextError>(res.to_vec());
+///
+/// assert_eq!(token.parse_peek(&b"ab\xff"[..]), Ok((&b"\xff"[..], (1, &b"ab"[..]))));
+/// ```
+#[inline(always)]
+pub fn any_of<'h, Input, Error>(
+    regexes: Vec<regex::bytes::Regex>,
+) -> AnyOfParser<'h, Input, regex::bytes::Regex, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    regex::bytes::Regex: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Error: ParserError<Input> + 'static,
+{
+    AnyOfParser {
+        res: regexes,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+pub struct Utf8Parser<I, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    E: ParserError<I>,
+{
+    re: regex::bytes::Regex,
+    _marker: core::marker::PhantomData<(I, E)>,
+}
+
+impl<I, E> Parser<I, <I as Stream>::Slice, E> for Utf8Parser<I, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    <I as Stream>::Slice: AsRef<[u8]>,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<<I as Stream>::Slice, E> {
+        if <I as StreamIsPartial>::is_partial_supported() {
+            utf8_impl::<_, _, true>(input, &self.re)
+        } else {
+            utf8_impl::<_, _, false>(input, &self.re)
+        }
+    }
+}
+
+/// Matches a Unicode regex against a byte stream that holds UTF-8 text.
+///
+/// The pattern is compiled for the bytes engine with Unicode mode on, in which `\w`,
+/// `\p{Greek}` and the other classes match exactly what they match on a `&str`, but never an
+/// invalid UTF-8 sequence. The search runs on the bytes directly, and only the matched prefix
+/// is validated afterwards, so lexing a buffer token by token does not decode the rest of the
+/// buffer on every call. The match is returned as a slice of the byte stream.
+///
+/// A match can stop short of an invalid sequence but not cross it; a pattern that turns Unicode
+/// mode off with `(?-u)` and matches invalid bytes fails instead. On partial input, a sequence
+/// cut off by the end of the buffer is treated like the end of the buffer: a match reaching it
+/// returns `Incomplete`, since the rest of the character may arrive later, and so does no match
+/// at all.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::bytes::regex_utf8;
+///
+/// fn word<'i>(input: &mut &'i [u8]) -> ModalResult<&'i [u8]> {
+///     regex_utf8(r"^\w+").parse_next(input)
+/// }
+///
+/// assert_eq!(word.parse_peek("héllo wörld".as_bytes()), Ok((&b" w\xc3\xb6rld"[..], "héllo".as_bytes())));
+/// ```
+#[inline(always)]
+pub fn regex_utf8<Input, Re, Error>(re: Re) -> Utf8Parser<Input, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: BytesRegexPattern<Output = regex::bytes::Regex>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    Utf8Parser {
+        re: re.into_regex(),
+        _marker: core::marker::PhantomData,
+    }
+}
+
+pub struct UnicodeParser<I, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    E: ParserError<I>,
+{
+    inner: Utf8Parser<I, E>,
+    /// Steps a failed match through the input to find out how far it got.
+    dfa: hybrid::dfa::DFA,
+    cache: hybrid::dfa::Cache,
+}
+
+impl<I, E> UnicodeParser<I, E>
+where
+    I: Stream,
+    E: ParserError<I>,
+{
+    /// Returns the error for the invalid sequence a failed match at the start of `bytes` ran
+    /// into, if the match was still alive when it reached one.
+    fn blocking_sequence(&mut self, bytes: &[u8]) -> Option<Utf8Error> {
+        let input = regex_automata::Input::new(bytes).anchored(regex_automata::Anchored::Yes);
+        let mut sid = self.dfa.start_state_forward(&mut self.cache, &input).ok()?;
+        let mut at = 0;
+        while at < bytes.len() {
+            let width = match bytes[at] {
+                0x00..=0x7f => 1,
+                0xc2..=0xdf => 2,
+                0xe0..=0xef => 3,
+                0xf0..=0xf4 => 4,
+                _ => 0,
+            };
+            let Some(ch) = bytes
+                .get(at..at + width)
+                .filter(|ch| !ch.is_empty() && core::str::from_utf8(ch).is_ok())
+            else {
+                // The text before `at` is valid, so this reports the sequence at `at`, unless it
+                // is only cut off by the end of the input.
+                let end = bytes.len().min(at + 4);
+                return core::str::from_utf8(&bytes[..end])
+                    .err()
+                    .filter(|e| e.error_len().is_some());
+            };
+            for &byte in ch {
+                sid = self.dfa.next_state(&mut self.cache, sid, byte).ok()?;
+                // A quit state means a Unicode word boundary the lazy DFA cannot follow.
+                if sid.is_dead() || sid.is_quit() {
+                    return None;
+                }
+            }
+            at += width;
+        }
+        None
+    }
+}
+
+impl<I, E> Parser<I, <I as Stream>::Slice, E> for UnicodeParser<I, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    <I as Stream>::Slice: AsRef<[u8]>,
+    E: ParserError<I> + FromExternalError<I, Utf8Error>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<<I as Stream>::Slice, E> {
+        self.inner.parse_next(input).map_err(|e| {
+            if e.is_incomplete() {
+                return e;
+            }
+            match self.blocking_sequence(input.peek_finish().as_ref()) {
+                Some(utf8) => E::from_external_error(input, utf8),
+                None => e,
+            }
+        })
+    }
+}
+
+/// Compiles `pattern` with the bytes engine in Unicode mode and matches it against a byte
+/// stream holding UTF-8 text.
+///
+/// This gives byte streams the Unicode property classes such as `\p{L}` with the same meaning
+/// as on `&str`, and returns the match as a slice of the byte stream. It works like
+/// [`regex_utf8`], except that a failed match where the remaining input contains invalid UTF-8
+/// reports the [`Utf8Error`] through [`FromExternalError`], so the error says why the text
+/// could not be read rather than just that the pattern did not match. Only a match attempt that
+/// got as far as the invalid sequence blames it; one that fails earlier, or a match that stops
+/// short of the sequence, is unaffected by it.
+///
+/// On partial input, a sequence cut off by the end of the buffer returns `Incomplete`, like in
+/// [`regex_utf8`].
+///
+/// The pattern is compiled twice: once into the bytes regex of [`regex_utf8`], which finds the
+/// match, and once into a lazy DFA that is only run after a failed match to decide whether
+/// invalid UTF-8 is to blame.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::bytes::regex_unicode;
+///
+/// fn word<'i>(input: &mut &'i [u8]) -> ModalResult<&'i [u8]> {
+///     regex_unicode(r"^\p{L}+").parse_next(input)
+/// }
+///
+/// assert_eq!(word.parse_peek("Straße 1".as_bytes()), Ok((&b" 1"[..], "Straße".as_bytes())));
+/// let err = word.parse_peek(b"\xff").unwrap_err().into_inner().unwrap();
+/// assert!(err.cause().unwrap().to_string().contains("invalid utf-8"));
+/// ```
+#[inline(always)]
+pub fn regex_unicode<Input, Error>(pattern: &str) -> UnicodeParser<Input, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Error: ParserError<Input> + FromExternalError<Input, Utf8Error> + 'static,
+{
+    let dfa = hybrid::dfa::DFA::builder()
+        .configure(hybrid::dfa::DFA::config().unicode_word_boundary(true))
+        .build(pattern)
+        .unwrap_or_else(|e| panic!("failed to compile regex for unicode parser: {:?}", e));
+    UnicodeParser {
+        inner: regex_utf8(pattern),
+        cache: dfa.create_cache(),
+        dfa,
+    }
+}
+
+fn utf8_impl<I, E, const PARTIAL: bool>(
+    input: &mut I,
+    re: &regex::bytes::Regex,
+) -> Result<<I as Stream>::Slice, E>
+where
+    I: Stream + StreamIsPartial + Clone,
+    <I as Stream>::Slice: AsRef<[u8]>,
+    E: ParserError<I>,
+{
+    let hay = input.peek_finish();
+    let bytes = hay.as_ref();
+    let partial = PARTIAL && input.is_partial();
+
+    match re.find(bytes) {
+        Some(m) if m.start() == 0 && core::str::from_utf8(m.as_bytes()).is_ok() => {
+            if partial && text_ends_at(&bytes[m.end()..]) {
+                Err(E::incomplete(input, Needed::Unknown))
+            } else {
+                Ok(input.next_slice(m.end()))
+            }
+        }
+        _ if partial => Err(E::incomplete(input, Needed::Unknown)),
+        _ => Err(ParserError::from_input(input)),
+    }
+}
+
+/// Returns whether the UTF-8 text of a buffer ends where `rest` begins, i.e. `rest` is empty
+/// or holds only the start of a sequence cut off by the end of the buffer.
+fn text_ends_at(rest: &[u8]) -> bool {
+    rest.len() < 4
+        && match core::str::from_utf8(rest) {
+            Ok(text) => text.is_empty(),
+            Err(e) => e.valid_up_to() == 0 && e.error_len().is_none(),
+        }
+}
+
+impl<Slice, L> Captures<Slice, L>
+where
+    Slice: AsRef<[u8]>,
+    L: CaptureLocations<Input = [u8]>,
+{
+    /// Returns group `i` as an owned `String`, replacing invalid UTF-8 with `U+FFFD`.
+    ///
+    /// Returns `None` if the group does not exist or did not participate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use winnow::error::ContextError;
+    /// use winnow::prelude::*;
+    /// use winnow_regex::bytes::captures;
+    ///
+    /// let caps = captures::<_, _, ContextError>(r"^(?-u)name=([^;]*)")
+    ///     .parse_next(&mut &b"name=caf\xe9;"[..])
+    ///     .unwrap();
+    /// assert_eq!(caps.to_string_lossy(1).as_deref(), Some("caf\u{fffd}"));
+    /// assert_eq!(caps.to_string_lossy(2), None);
+    /// ```
+    pub fn to_string_lossy(&self, i: usize) -> Option<String> {
+        self.get(i)
+            .map(|group| String::from_utf8_lossy(group).into_owned())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use winnow::error::{ContextError, ErrMode, Needed};
+    use winnow::error::{ContextError, EmptyError, ErrMode, Needed};
     use winnow::prelude::*;
 
     #[test]
@@ -147,6 +663,144 @@ mod tests {
         );
     }
 
+    #[test]
+    fn dotall() {
+        let mut p = regex_dotall::<_, ErrMode<ContextError>>(r"^(?-u).+");
+        assert_eq!(
+            p.parse_peek(&b"\x01\n\xff"[..]),
+            Ok((&b""[..], &b"\x01\n\xff"[..]))
+        );
+        let mut p = regex::<_, _, ErrMode<ContextError>>(r"^(?-u).+");
+        assert_eq!(
+            p.parse_peek(&b"\x01\n\xff"[..]),
+            Ok((&b"\n\xff"[..], &b"\x01"[..]))
+        );
+    }
+
+    #[test]
+    fn utf8_boundaries() {
+        use winnow::stream::Partial;
+        let mut p = regex_utf8::<_, _, ErrMode<ContextError>>(r"^\w+");
+        // The buffer ends in the middle of `é`.
+        assert_eq!(
+            p.parse_peek(Partial::new(&b"caf\xc3"[..])),
+            Err(ErrMode::Incomplete(Needed::Unknown))
+        );
+        assert_eq!(
+            p.parse_peek(Partial::new(&b"caf\xc3\xa9!"[..])),
+            Ok((Partial::new(&b"!"[..]), "café".as_bytes()))
+        );
+
+        let mut p = regex_utf8::<_, _, ErrMode<ContextError>>(r"^\w+");
+        assert_eq!(
+            p.parse_peek(&b"ab\xffcd"[..]),
+            Ok((&b"\xffcd"[..], &b"ab"[..]))
+        );
+        assert!(p.parse_peek(&b"\xff"[..]).is_err());
+
+        // Only the match is validated, and it may not contain invalid bytes.
+        let mut p = regex_utf8::<_, _, ErrMode<ContextError>>(r"^(?-u)a.");
+        assert!(p.parse_peek(&b"a\xff"[..]).is_err());
+        assert_eq!(p.parse_peek(&b"ab\xff"[..]), Ok((&b"\xff"[..], &b"ab"[..])));
+    }
+
+    #[test]
+    fn ascii_str() {
+        let mut p = regex_ascii_str::<_, _, ErrMode<ContextError>>(r"^(?-u)\S+");
+        assert_eq!(p.parse_peek(&b"GET /"[..]), Ok((&b" /"[..], "GET")));
+        let mut input = &b"caf\xc3\xa9 /"[..];
+        assert!(p.parse_next(&mut input).is_err());
+        assert_eq!(input, b"caf\xc3\xa9 /");
+    }
+
+    #[test]
+    fn to_array() {
+        let caps = captures::<_, _, ErrMode<ContextError>>(r"^(\d+)x(\d+)(px)?")
+            .parse_next(&mut &b"800x600;"[..])
+            .unwrap();
+        assert_eq!(caps.to_array::<2>(), None);
+        assert_eq!(caps.to_array::<3>(), None);
+
+        let caps = captures::<_, _, ErrMode<ContextError>>(r"^(\d+)x(\d+)")
+            .parse_next(&mut &b"800x600;"[..])
+            .unwrap();
+        let [w, h] = caps.to_array().unwrap();
+        assert_eq!((w, h), (&b"800"[..], &b"600"[..]));
+    }
+
+    #[test]
+    fn unicode_letters() {
+        use winnow::stream::Partial;
+        let mut p = regex_unicode::<_, ContextError>(r"^\p{L}+");
+        assert_eq!(
+            p.parse_peek("Δέλτα!".as_bytes()),
+            Ok((&b"!"[..], "Δέλτα".as_bytes()))
+        );
+        assert_eq!(p.parse_peek(&b"ab\xff"[..]), Ok((&b"\xff"[..], &b"ab"[..])));
+        let err = p.parse_peek(&b"\xffab"[..]).unwrap_err();
+        assert!(err.cause().is_some());
+        assert!(p.parse_peek(&b"1"[..]).unwrap_err().cause().is_none());
+        // The attempt must reach the invalid sequence to blame it.
+        assert!(p.parse_peek(&b"1\xff"[..]).unwrap_err().cause().is_none());
+        let mut p = regex_unicode::<_, ContextError>(r"^\p{L}+\d");
+        assert!(p.parse_peek(&b"ab\xff"[..]).unwrap_err().cause().is_some());
+        assert!(p.parse_peek(&b"a-\xff"[..]).unwrap_err().cause().is_none());
+
+        let mut p = regex_unicode::<_, ErrMode<ContextError>>(r"^\p{L}+");
+        assert_eq!(
+            p.parse_peek(Partial::new(&b"ab\xce"[..])),
+            Err(ErrMode::Incomplete(Needed::Unknown))
+        );
+    }
+
+    #[test]
+    fn try_index() {
+        let caps = captures::<_, _, ErrMode<ContextError>>(r"^(\d+)(px)?")
+            .parse_next(&mut &b"80;"[..])
+            .unwrap();
+        assert_eq!(caps.try_index(1), Ok(&b"80"[..]));
+        assert_eq!(
+            caps.try_index(2),
+            Err(crate::CaptureError::NotParticipating { index: 2 })
+        );
+        assert_eq!(
+            caps.try_index(5),
+            Err(crate::CaptureError::OutOfRange { index: 5, len: 3 })
+        );
+    }
+
+    #[test]
+    fn regex_parse_bytes() {
+        let mut int = regex_parse::<_, _, i64, ContextError>(r"^-?\d+");
+        assert_eq!(int.parse_peek(&b"12;"[..]), Ok((&b";"[..], 12)));
+        assert!(int.parse_peek(&b"99999999999999999999"[..]).is_err());
+
+        // `(?-u:.)` lets the match cover bytes that are not UTF-8.
+        let mut raw = regex_parse::<_, _, String, ContextError>(r"^(?-u:.)+");
+        let mut input = &b"a\xffb"[..];
+        let err = raw.parse_next(&mut input).unwrap_err();
+        assert!(err.cause().unwrap().to_string().contains("invalid utf-8"));
+        assert_eq!(input, &b"a\xffb"[..]);
+    }
+
+    #[test]
+    fn byte_pattern() {
+        let pattern: &[u8] = br"^\d+";
+        let mut p = regex::<_, _, EmptyError>(pattern);
+        assert_eq!(p.parse_peek(&b"12;"[..]), Ok((&b";"[..], &b"12"[..])));
+
+        let invalid: &[u8] = b"^\xff";
+        assert!(matches!(
+            BytesRegexPattern::try_into_regex(invalid),
+            Err(Error::Utf8(_))
+        ));
+        let unclosed: &[u8] = b"^(";
+        assert!(matches!(
+            BytesRegexPattern::try_into_regex(unclosed),
+            Err(Error::Regex(_))
+        ));
+    }
+
     #[test]
     fn test_re() {
         let re = regex::Regex::new(r"\d+").unwrap();
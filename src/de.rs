@@ -0,0 +1,254 @@
+//! Deserialize named capture groups into a user-defined type with [`serde`].
+use crate::{RegexPattern, captures_impl, regex_trait::*};
+use core::fmt::Debug;
+use serde::de::{
+    DeserializeOwned, Deserializer, Error as _, IntoDeserializer, Unexpected, Visitor,
+    value::{Error as ValueError, MapDeserializer},
+};
+use winnow::{
+    Parser,
+    error::{FromExternalError, ParserError},
+    stream::{Offset, Stream, StreamIsPartial},
+};
+
+pub struct DeserializeCapturesParser<'h, I, R, T, E>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    _marker: core::marker::PhantomData<(&'h (), I, T, E)>,
+}
+
+impl<'h, I, R, T, E> Parser<I, T, E> for DeserializeCapturesParser<'h, I, R, T, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    <I as Stream>::Slice: AsRef<str>,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    R::CaptureLocations: CaptureLocations<Input = str>,
+    T: DeserializeOwned,
+    E: ParserError<I> + FromExternalError<I, ValueError>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<T, E> {
+        let start = input.checkpoint();
+        let (slice, locs) = if <I as StreamIsPartial>::is_partial_supported() {
+            captures_impl::<_, _, _, true>(input, &self.re)
+        } else {
+            captures_impl::<_, _, _, false>(input, &self.re)
+        }?;
+
+        let hay: &str = slice.as_ref();
+        // Non-participating groups are left out, so `Option` fields deserialize as `None`.
+        let fields = self.re.capture_names().enumerate().filter_map(|(i, name)| {
+            let (start, end) = locs.get(i)?;
+            Some((name?, CaptureDeserializer(&hay[start..end])))
+        });
+
+        T::deserialize(MapDeserializer::new(fields)).map_err(|e| {
+            input.reset(&start);
+            E::from_external_error(input, e)
+        })
+    }
+}
+
+/// Creates a parser that deserializes the named capture groups of a match into `T`.
+///
+/// Each participating named group becomes a map entry keyed by the group name. Values are
+/// deserialized from the captured text, so numeric, `bool` and `char` fields are parsed from
+/// their string form. Groups that did not participate in the match are absent from the map,
+/// which makes them `None` for `Option` fields and an error for required ones. Unnamed groups
+/// are ignored.
+///
+/// If deserialization fails, the input is left untouched and the serde error is reported through
+/// [`FromExternalError`].
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use serde::Deserialize;
+/// use winnow::prelude::*;
+/// use winnow_regex::de::deserialize_captures;
+///
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// struct Size {
+///     width: u32,
+///     height: u32,
+///     unit: Option<String>,
+/// }
+///
+/// fn size(input: &mut &str) -> ModalResult<Size> {
+///     deserialize_captures(r"^(?<width>\d+)x(?<height>\d+)(?<unit>px|em)?").parse_next(input)
+/// }
+///
+/// assert_eq!(
+///     size.parse_peek("800x600 rest"),
+///     Ok((" rest", Size { width: 800, height: 600, unit: None }))
+/// );
+/// ```
+#[inline(always)]
+pub fn deserialize_captures<'h, T, Input, Re, Error>(
+    re: Re,
+) -> DeserializeCapturesParser<'h, Input, Re::Output, T, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    T: DeserializeOwned,
+    Error: ParserError<Input> + 'static,
+{
+    let re = re.into_regex();
+
+    DeserializeCapturesParser {
+        re,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+/// Deserializes a single captured group, parsing scalars from their textual form.
+struct CaptureDeserializer<'a>(&'a str);
+
+macro_rules! deserialize_from_str {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                match self.0.parse() {
+                    Ok(v) => visitor.$visit(v),
+                    Err(_) => Err(Self::Error::invalid_value(Unexpected::Str(self.0), &visitor)),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for CaptureDeserializer<'_> {
+    type Error = ValueError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_str(self.0)
+    }
+
+    deserialize_from_str! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        IntoDeserializer::<Self::Error>::into_deserializer(self.0)
+            .deserialize_enum(name, variants, visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        str string bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, ValueError> for CaptureDeserializer<'_> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use winnow::error::ContextError;
+    use winnow::prelude::*;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Level {
+        Info,
+        Warn,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Record {
+        level: Level,
+        code: u16,
+        ok: bool,
+        note: Option<String>,
+    }
+
+    fn record(s: &mut &str) -> ModalResult<Record> {
+        deserialize_captures(r"^(?<level>\w+) (?<code>\d+) (?<ok>true|false)(?: (?<note>\w+))?")
+            .parse_next(s)
+    }
+
+    #[test]
+    fn coerces_and_omits_missing_groups() {
+        assert_eq!(
+            record.parse_peek("warn 404 false;"),
+            Ok((
+                ";",
+                Record {
+                    level: Level::Warn,
+                    code: 404,
+                    ok: false,
+                    note: None,
+                }
+            ))
+        );
+        assert_eq!(
+            record.parse_peek("info 200 true fine"),
+            Ok((
+                "",
+                Record {
+                    level: Level::Info,
+                    code: 200,
+                    ok: true,
+                    note: Some("fine".to_owned()),
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn deserialize_error_does_not_consume() {
+        let mut input = "info 99999 true";
+        let res = deserialize_captures::<Record, _, _, ContextError>(
+            r"^(?<level>\w+) (?<code>\d+) (?<ok>true|false)",
+        )
+        .parse_next(&mut input);
+        assert!(res.is_err());
+        assert_eq!(input, "info 99999 true");
+    }
+}
@@ -0,0 +1,205 @@
+//! A [`Regex`] wrapper that records how often it runs, for finding hot patterns.
+use crate::{Error, RegexPattern, regex_trait::*};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Counters collected by an [`InstrumentedRegex`].
+///
+/// The counters are updated with relaxed atomics, so they can be read while parsers using the
+/// regex are running, possibly on other threads.
+#[derive(Debug, Default)]
+pub struct Stats {
+    invocations: AtomicU64,
+    matches: AtomicU64,
+    matched_bytes: AtomicU64,
+}
+
+impl Stats {
+    /// Returns how many times the regex was run.
+    pub fn invocations(&self) -> u64 {
+        self.invocations.load(Ordering::Relaxed)
+    }
+
+    /// Returns how many of the runs found a match.
+    pub fn matches(&self) -> u64 {
+        self.matches.load(Ordering::Relaxed)
+    }
+
+    /// Returns the total length of all matches found, in bytes.
+    ///
+    /// Runs that only check whether the regex matches do not know the match length and add
+    /// nothing.
+    pub fn matched_bytes(&self) -> u64 {
+        self.matched_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Resets every counter to zero.
+    pub fn reset(&self) {
+        self.invocations.store(0, Ordering::Relaxed);
+        self.matches.store(0, Ordering::Relaxed);
+        self.matched_bytes.store(0, Ordering::Relaxed);
+    }
+
+    fn record(&self, found: Option<(usize, usize)>) -> Option<(usize, usize)> {
+        self.invocations.fetch_add(1, Ordering::Relaxed);
+        if let Some((start, end)) = found {
+            self.matches.fetch_add(1, Ordering::Relaxed);
+            self.matched_bytes
+                .fetch_add((end - start) as u64, Ordering::Relaxed);
+        }
+        found
+    }
+}
+
+/// A regex that counts its runs and matched bytes into shared [`Stats`].
+///
+/// Parsers built from it behave exactly like parsers built from the wrapped regex. Take a
+/// handle to the counters with [`InstrumentedRegex::stats`] before handing the regex to a
+/// parser; clones of the regex share the same counters.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::{instrument::InstrumentedRegex, regex};
+///
+/// let re = InstrumentedRegex::new(r"^\d+").unwrap();
+/// let stats = re.stats();
+/// let mut digits = regex::<_, _, winnow::error::ContextError>(re);
+///
+/// assert_eq!(digits.parse_peek("123 abc"), Ok((" abc", "123")));
+/// assert!(digits.parse_peek("abc").is_err());
+/// assert_eq!((stats.invocations(), stats.matches(), stats.matched_bytes()), (2, 1, 3));
+/// ```
+#[derive(Debug, Clone)]
+pub struct InstrumentedRegex<R = regex::Regex> {
+    re: R,
+    stats: Arc<Stats>,
+}
+
+impl InstrumentedRegex {
+    /// Compiles `pattern` with [`regex::Regex::new`].
+    pub fn new(pattern: &str) -> Result<Self, Error> {
+        Ok(Self::from(regex::Regex::new(pattern)?))
+    }
+}
+
+impl<R> InstrumentedRegex<R> {
+    /// Returns a handle to the counters, which stays valid after the regex is moved.
+    pub fn stats(&self) -> Arc<Stats> {
+        self.stats.clone()
+    }
+
+    /// Returns the wrapped regex.
+    pub fn get_ref(&self) -> &R {
+        &self.re
+    }
+
+    /// Consumes the wrapper, returning the wrapped regex.
+    pub fn into_inner(self) -> R {
+        self.re
+    }
+}
+
+impl<R> From<R> for InstrumentedRegex<R> {
+    fn from(re: R) -> Self {
+        Self {
+            re,
+            stats: Arc::default(),
+        }
+    }
+}
+
+impl<R> RegexPattern for InstrumentedRegex<R> {
+    type Error = Error;
+    type Output = Self;
+
+    #[inline(always)]
+    fn try_into_regex(self) -> Result<Self::Output, Self::Error> {
+        Ok(self)
+    }
+}
+
+impl<R: Regex> Regex for InstrumentedRegex<R> {
+    type Haystack<'h> = R::Haystack<'h>;
+    type CaptureLocations = R::CaptureLocations;
+
+    #[inline]
+    fn capture_locations(&self) -> Self::CaptureLocations {
+        self.re.capture_locations()
+    }
+
+    #[inline]
+    fn as_str(&self) -> &str {
+        self.re.as_str()
+    }
+
+    #[inline]
+    fn capture_names(&self) -> impl Iterator<Item = Option<&str>> {
+        self.re.capture_names()
+    }
+
+    #[inline]
+    fn captures_read(
+        &self,
+        locs: &mut Self::CaptureLocations,
+        haystack: Self::Haystack<'_>,
+    ) -> Option<(usize, usize)> {
+        self.stats.record(self.re.captures_read(locs, haystack))
+    }
+
+    #[inline]
+    fn captures_read_at(
+        &self,
+        locs: &mut Self::CaptureLocations,
+        haystack: Self::Haystack<'_>,
+        at: usize,
+    ) -> Option<(usize, usize)> {
+        self.stats
+            .record(self.re.captures_read_at(locs, haystack, at))
+    }
+
+    #[inline]
+    fn find(&self, haystack: Self::Haystack<'_>) -> Option<(usize, usize)> {
+        self.stats.record(self.re.find(haystack))
+    }
+
+    #[inline]
+    fn is_match_at(&self, haystack: Self::Haystack<'_>, at: usize) -> bool {
+        let found = self.re.is_match_at(haystack, at);
+        self.stats.record(found.then_some((0, 0)));
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{captures, is_regex, regex};
+    use winnow::error::EmptyError;
+    use winnow::prelude::*;
+
+    #[test]
+    fn counts_every_backend_call() {
+        let re = InstrumentedRegex::new(r"^(\w+)").unwrap();
+        let stats = re.stats();
+        let mut p = captures::<_, _, EmptyError>(re.clone());
+        assert!(p.parse_peek("ab cd").is_ok());
+        assert!(is_regex::<_, _, EmptyError>(re).parse_peek("!").is_err());
+        assert_eq!(
+            (stats.invocations(), stats.matches(), stats.matched_bytes()),
+            (2, 1, 2)
+        );
+        stats.reset();
+        assert_eq!(stats.invocations(), 0);
+    }
+
+    #[test]
+    fn bytes_regex() {
+        let re = InstrumentedRegex::from(regex::bytes::Regex::new(r"^\d+").unwrap());
+        let stats = re.stats();
+        let mut p = regex::<_, _, EmptyError>(re);
+        assert_eq!(p.parse_peek(&b"12;"[..]), Ok((&b";"[..], &b"12"[..])));
+        assert_eq!(stats.matched_bytes(), 2);
+    }
+}
@@ -0,0 +1,415 @@
+//! Drive the partial-input parsers from an [`io::Read`] or [`io::BufRead`] source.
+use std::io::{self, BufRead, Read};
+use winnow::{
+    Parser, Partial,
+    error::ParserError,
+    stream::{Stream, StreamIsPartial},
+};
+
+/// The error of [`ReadStream::parse_next`].
+#[derive(Debug, thiserror::Error)]
+pub enum ReadError<E> {
+    /// Reading from the source failed.
+    #[error("failed to read input")]
+    Io(#[from] io::Error),
+    /// The parser failed on the data read so far.
+    #[error("failed to parse input")]
+    Parse(E),
+}
+
+/// A growable buffer filled from an [`io::Read`], parsed as a [`Partial`] byte stream.
+///
+/// [`ReadStream::parse_next`] runs a parser on the buffered bytes and, whenever it returns
+/// `Incomplete`, reads another chunk from the source and tries again. Once the source is
+/// exhausted the buffer is parsed as complete input, so a match that ends at the end of the
+/// data succeeds instead of asking for more. Consumed bytes are dropped from the buffer before
+/// the next read, so it only grows to hold the longest single parse.
+///
+/// The parser must work on `Partial<&[u8]>` of any lifetime, since the buffer moves as it
+/// grows, and its output cannot borrow from the buffer. In practice this means a `fn` item
+/// mapping matches to owned values; keep its regex in a `static` to compile it only once. The
+/// error type must be able to report `Incomplete`, i.e. be an
+/// [`ErrMode`](winnow::error::ErrMode).
+///
+/// # Example
+///
+/// ```
+/// use std::io::Read;
+/// use std::sync::LazyLock;
+/// use winnow::{Partial, prelude::*};
+/// use winnow_regex::{io::ReadStream, regex};
+///
+/// /// Hands out the data two bytes at a time, like a slow socket.
+/// struct Trickle<'a>(&'a [u8]);
+///
+/// impl Read for Trickle<'_> {
+///     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+///         let n = buf.len().min(2).min(self.0.len());
+///         buf[..n].copy_from_slice(&self.0[..n]);
+///         self.0 = &self.0[n..];
+///         Ok(n)
+///     }
+/// }
+///
+/// static NUMBER: LazyLock<regex::bytes::Regex> =
+///     LazyLock::new(|| regex::bytes::Regex::new(r"^\d+,?").unwrap());
+///
+/// fn number(input: &mut Partial<&[u8]>) -> ModalResult<Vec<u8>> {
+///     regex(NUMBER.clone()).map(<[u8]>::to_vec).parse_next(input)
+/// }
+///
+/// let mut stream = ReadStream::new(Trickle(b"12345,678"));
+/// assert_eq!(stream.parse_next(&mut number).unwrap(), b"12345,");
+/// assert_eq!(stream.parse_next(&mut number).unwrap(), b"678");
+/// assert!(stream.is_finished());
+/// ```
+#[derive(Debug)]
+pub struct ReadStream<R> {
+    reader: R,
+    buf: Vec<u8>,
+    /// Start of the unconsumed bytes in `buf`.
+    pos: usize,
+    chunk_size: usize,
+    eof: bool,
+}
+
+impl<R: Read> ReadStream<R> {
+    /// Wraps `reader`, reading 8 KiB at a time.
+    pub fn new(reader: R) -> Self {
+        Self::with_chunk_size(reader, 8 * 1024)
+    }
+
+    /// Wraps `reader`, reading up to `chunk_size` bytes each time more input is needed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero.
+    pub fn with_chunk_size(reader: R, chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "chunk size must not be zero");
+        Self {
+            reader,
+            buf: Vec::new(),
+            pos: 0,
+            chunk_size,
+            eof: false,
+        }
+    }
+
+    /// Runs `parser` on the buffered input, reading more from the source while it returns
+    /// `Incomplete`.
+    ///
+    /// On success the consumed bytes are removed from the stream. On a parse error nothing is
+    /// consumed, so a different parser can be tried on the same input.
+    pub fn parse_next<P, O, E>(&mut self, parser: &mut P) -> Result<O, ReadError<E>>
+    where
+        P: for<'i> Parser<Partial<&'i [u8]>, O, E>,
+        E: for<'i> ParserError<Partial<&'i [u8]>>,
+    {
+        loop {
+            match attempt(parser, &self.buf[self.pos..], self.eof) {
+                Ok((output, used)) => {
+                    self.pos += used;
+                    return Ok(output);
+                }
+                Err(e) if e.is_incomplete() && !self.eof => self.fill()?,
+                Err(e) => return Err(ReadError::Parse(e)),
+            }
+        }
+    }
+
+    /// Returns the bytes read from the source but not yet consumed by a parser.
+    pub fn buffer(&self) -> &[u8] {
+        &self.buf[self.pos..]
+    }
+
+    /// Returns `true` once the source is exhausted and every buffered byte has been consumed.
+    pub fn is_finished(&self) -> bool {
+        self.eof && self.pos == self.buf.len()
+    }
+
+    /// Consumes the adapter, returning the wrapped reader. Buffered bytes are lost.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Reads the next chunk, dropping the consumed bytes first.
+    fn fill(&mut self) -> io::Result<()> {
+        self.buf.drain(..self.pos);
+        self.pos = 0;
+        let len = self.buf.len();
+        self.buf.resize(len + self.chunk_size, 0);
+        let read = loop {
+            match self.reader.read(&mut self.buf[len..]) {
+                Ok(read) => break read,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => {
+                    self.buf.truncate(len);
+                    return Err(e);
+                }
+            }
+        };
+        self.buf.truncate(len + read);
+        self.eof = read == 0;
+        Ok(())
+    }
+}
+
+/// Like [`ReadStream`], but parses straight from the buffer of an [`io::BufRead`] source.
+///
+/// Whatever [`BufRead::fill_buf`] returns is parsed in place, and a match is removed with
+/// [`BufRead::consume`], so sources that already buffer, such as a [`BufReader`], are parsed
+/// without copying. Only when a parser needs more than the source has buffered are the bytes
+/// copied into a buffer of the adapter, which is extended by further `fill_buf` calls until the
+/// parser has enough; once it is used up, parsing goes back to the source's buffer.
+///
+/// The same rules as for [`ReadStream`] apply to the parser: it must work on
+/// `Partial<&[u8]>` of any lifetime, return owned output and report `Incomplete`. It also
+/// accepts a `&mut dyn BufRead`.
+///
+/// [`BufReader`]: std::io::BufReader
+///
+/// # Example
+///
+/// ```
+/// use std::fs::File;
+/// use std::io::BufReader;
+/// use winnow::{Partial, prelude::*};
+/// use winnow_regex::{bytes::captures, io::BufReadStream};
+///
+/// fn record(input: &mut Partial<&[u8]>) -> ModalResult<(String, u32)> {
+///     captures(r"^(\w+)=(\d+)\n")
+///         .map(|caps| {
+///             let key = String::from_utf8_lossy(&caps[1]).into_owned();
+///             let value = std::str::from_utf8(&caps[2]).unwrap().parse().unwrap();
+///             (key, value)
+///         })
+///         .parse_next(input)
+/// }
+///
+/// let path = std::env::temp_dir().join("winnow-regex-bufread-example.txt");
+/// std::fs::write(&path, "width=80\nheight=24\n").unwrap();
+///
+/// // A tiny buffer, so records straddle refills.
+/// let file = BufReader::with_capacity(4, File::open(&path).unwrap());
+/// let mut stream = BufReadStream::new(file);
+/// assert_eq!(stream.parse_next(&mut record).unwrap(), ("width".to_owned(), 80));
+/// assert_eq!(stream.parse_next(&mut record).unwrap(), ("height".to_owned(), 24));
+/// assert!(stream.is_finished().unwrap());
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct BufReadStream<B> {
+    reader: B,
+    /// Bytes taken out of the source because a parse needed more than it had buffered.
+    carry: Vec<u8>,
+    /// Start of the unconsumed bytes in `carry`.
+    pos: usize,
+    eof: bool,
+}
+
+impl<B: BufRead> BufReadStream<B> {
+    /// Wraps `reader`.
+    pub fn new(reader: B) -> Self {
+        Self {
+            reader,
+            carry: Vec::new(),
+            pos: 0,
+            eof: false,
+        }
+    }
+
+    /// Runs `parser` on the buffered input, reading more from the source while it returns
+    /// `Incomplete`.
+    ///
+    /// See [`ReadStream::parse_next`].
+    pub fn parse_next<P, O, E>(&mut self, parser: &mut P) -> Result<O, ReadError<E>>
+    where
+        P: for<'i> Parser<Partial<&'i [u8]>, O, E>,
+        E: for<'i> ParserError<Partial<&'i [u8]>>,
+    {
+        loop {
+            if self.pos == self.carry.len() {
+                // Nothing carried over, so parse the source's buffer in place.
+                self.carry.clear();
+                self.pos = 0;
+                let buf = fill_buf(&mut self.reader)?;
+                let eof = buf.is_empty();
+                match attempt(parser, buf, eof) {
+                    Ok((output, used)) => {
+                        self.reader.consume(used);
+                        return Ok(output);
+                    }
+                    Err(e) if e.is_incomplete() && !eof => {
+                        self.carry.extend_from_slice(buf);
+                        let len = buf.len();
+                        self.reader.consume(len);
+                    }
+                    Err(e) => return Err(ReadError::Parse(e)),
+                }
+            } else {
+                match attempt(parser, &self.carry[self.pos..], self.eof) {
+                    Ok((output, used)) => {
+                        self.pos += used;
+                        return Ok(output);
+                    }
+                    Err(e) if e.is_incomplete() && !self.eof => {}
+                    Err(e) => return Err(ReadError::Parse(e)),
+                }
+            }
+
+            let buf = fill_buf(&mut self.reader)?;
+            self.eof = buf.is_empty();
+            self.carry.drain(..self.pos);
+            self.pos = 0;
+            self.carry.extend_from_slice(buf);
+            let len = buf.len();
+            self.reader.consume(len);
+        }
+    }
+
+    /// Returns `true` once the source is exhausted and every byte has been consumed.
+    ///
+    /// This may call [`BufRead::fill_buf`] to find out whether the source has more data.
+    pub fn is_finished(&mut self) -> io::Result<bool> {
+        Ok(self.pos == self.carry.len() && fill_buf(&mut self.reader)?.is_empty())
+    }
+
+    /// Consumes the adapter, returning the wrapped reader. Bytes copied out of its buffer by a
+    /// parse that needed more input, but not consumed, are lost.
+    pub fn into_inner(self) -> B {
+        self.reader
+    }
+}
+
+/// Calls [`BufRead::fill_buf`], retrying when it is interrupted.
+fn fill_buf<B: BufRead>(reader: &mut B) -> io::Result<&[u8]> {
+    loop {
+        match reader.fill_buf() {
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            // Re-borrow so the returned slice does not keep the loop's borrow alive.
+            Ok(_) => break,
+            Err(e) => return Err(e),
+        }
+    }
+    reader.fill_buf()
+}
+
+/// Runs `parser` on `bytes`, as complete input once the source is exhausted, returning its
+/// output and the number of bytes consumed.
+fn attempt<P, O, E>(parser: &mut P, bytes: &[u8], eof: bool) -> Result<(O, usize), E>
+where
+    P: for<'i> Parser<Partial<&'i [u8]>, O, E>,
+    E: for<'i> ParserError<Partial<&'i [u8]>>,
+{
+    let mut input = Partial::new(bytes);
+    if eof {
+        let _ = input.complete();
+    }
+    let output = parser.parse_next(&mut input)?;
+    Ok((output, bytes.len() - input.eof_offset()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bytes::{captures, regex};
+    use winnow::error::ErrMode;
+    use winnow::prelude::*;
+
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = buf.len().min(3).min(self.0.len());
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.0 = &self.0[n..];
+            Ok(n)
+        }
+    }
+
+    fn number(input: &mut Partial<&[u8]>) -> ModalResult<Vec<u8>> {
+        regex(r"^\d+").map(<[u8]>::to_vec).parse_next(input)
+    }
+
+    fn space(input: &mut Partial<&[u8]>) -> ModalResult<()> {
+        regex(r"^ +").void().parse_next(input)
+    }
+
+    fn value(input: &mut Partial<&[u8]>) -> ModalResult<Vec<u8>> {
+        captures(r"^(\w+)=(\w+)")
+            .map(|caps| caps[2].to_vec())
+            .parse_next(input)
+    }
+
+    #[test]
+    fn digits_across_small_reads() {
+        let mut stream = ReadStream::with_chunk_size(Trickle(b"1234567 89"), 2);
+        assert_eq!(stream.parse_next(&mut number).unwrap(), b"1234567");
+        stream.parse_next(&mut space).unwrap();
+        assert_eq!(stream.parse_next(&mut number).unwrap(), b"89");
+        assert!(stream.is_finished());
+    }
+
+    #[test]
+    fn parse_error_consumes_nothing() {
+        let mut stream = ReadStream::new(Trickle(b"key=value"));
+        assert!(matches!(
+            stream.parse_next(&mut number),
+            Err(ReadError::Parse(ErrMode::Backtrack(_)))
+        ));
+        assert_eq!(stream.parse_next(&mut value).unwrap(), b"value");
+        assert_eq!(stream.buffer(), b"");
+    }
+
+    #[test]
+    fn bufread_zero_copy_and_carry() {
+        let data: &[u8] = b"12 3456789 0";
+        // `&[u8]` buffers everything, so every parse runs in place.
+        let mut stream = BufReadStream::new(data);
+        assert_eq!(stream.parse_next(&mut number).unwrap(), b"12");
+        stream.parse_next(&mut space).unwrap();
+        assert_eq!(stream.parse_next(&mut number).unwrap(), b"3456789");
+        assert_eq!(stream.carry, b"");
+
+        // A 3-byte buffer makes numbers straddle refills.
+        let reader = io::BufReader::with_capacity(3, Trickle(data));
+        let mut stream = BufReadStream::new(reader);
+        let mut tokens = vec![];
+        while !stream.is_finished().unwrap() {
+            tokens.push(stream.parse_next(&mut number).unwrap());
+            if !stream.is_finished().unwrap() {
+                stream.parse_next(&mut space).unwrap();
+            }
+        }
+        assert_eq!(tokens, [&b"12"[..], b"3456789", b"0"]);
+    }
+
+    #[test]
+    fn bufread_dyn_parse_error() {
+        let mut data: &[u8] = b"x=1";
+        let reader: &mut dyn BufRead = &mut data;
+        let mut stream = BufReadStream::new(reader);
+        assert!(matches!(
+            stream.parse_next(&mut number),
+            Err(ReadError::Parse(ErrMode::Backtrack(_)))
+        ));
+        assert_eq!(stream.parse_next(&mut value).unwrap(), b"1");
+    }
+
+    #[test]
+    fn io_error() {
+        struct Broken;
+
+        impl Read for Broken {
+            fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::other("broken pipe"))
+            }
+        }
+
+        let mut stream = ReadStream::new(Broken);
+        assert!(matches!(
+            stream.parse_next(&mut number),
+            Err(ReadError::Io(_))
+        ));
+    }
+}
@@ -1,29 +1,57 @@
 #![doc = include_str!("../README.md")]
 pub use winnow;
 
+#[cfg(feature = "automata")]
+pub mod automata;
+pub mod backend;
+mod builder;
 pub mod bytes;
+#[cfg(feature = "serde")]
+pub mod de;
+#[cfg(feature = "instrument")]
+pub mod instrument;
+#[cfg(feature = "io")]
+pub mod io;
 pub mod regex_trait;
 
+pub use builder::RegexBuilder;
+#[cfg(feature = "macros")]
+pub use winnow_regex_macros::regex;
+
+use core::borrow::Borrow;
 use core::fmt::Debug;
 use core::ops::{Index, Range};
+use core::str::FromStr;
 use regex_trait::*;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
 use winnow::{
     Parser,
-    error::{Needed, ParserError},
-    stream::{Offset, Stream, StreamIsPartial},
+    error::{ContextError, ErrMode, FromExternalError, Needed, ParserError},
+    stream::{LocatingSlice, Location, Offset, Stream, StreamIsPartial},
 };
 
 #[derive(Debug, Clone, thiserror::Error)]
 #[error(transparent)]
+#[non_exhaustive]
 pub enum Error {
     Regex(#[from] regex::Error),
+    /// A pattern given as bytes was not valid UTF-8.
+    Utf8(#[from] core::str::Utf8Error),
+    #[cfg(feature = "automata")]
+    Automata(#[from] Box<regex_automata::meta::BuildError>),
+    #[cfg(feature = "lite")]
+    Lite(#[from] regex_lite::Error),
+    #[cfg(feature = "fancy")]
+    Fancy(#[from] Box<fancy_regex::Error>),
 }
 
 /// A trait representing types that can be converted into a compiled [`Regex`] pattern.
 ///
 /// This is used by the `regex` parser to generically accept either a `&str` or an already-compiled
 /// [`Regex`] object. Implementors of this trait can be converted into a `Regex` via the
-/// [`try_into_regex`] method, allowing flexible API usage.
+/// [`try_into_regex`](Self::try_into_regex) method, allowing flexible API usage.
 ///
 /// # Associated Types
 ///
@@ -99,6 +127,296 @@ where
 {
     slice: Slice,
     locs: L,
+    names: CaptureNames,
+    /// Offset of `slice` within the haystack `locs` refer to.
+    base: usize,
+}
+
+/// Capture group names of a regex in pattern order, shared between all matches of a parser.
+type CaptureNames = Arc<[Option<Box<str>>]>;
+
+fn capture_names<R: Regex>(re: &R) -> CaptureNames {
+    re.capture_names().map(|name| name.map(Box::from)).collect()
+}
+
+#[allow(clippy::len_without_is_empty)]
+impl<Slice, L> Captures<Slice, L>
+where
+    L: CaptureLocations,
+{
+    /// Returns the number of groups in the pattern, including the implicit group 0.
+    pub fn len(&self) -> usize {
+        self.locs.len()
+    }
+
+    /// Returns the bounds of group `i` within `slice`.
+    fn span(&self, i: usize) -> Option<(usize, usize)> {
+        let (start, end) = self.locs.get(i)?;
+        Some((start - self.base, end - self.base))
+    }
+
+    /// Returns the byte range of group `i` within the matched slice, or `None` if it does not
+    /// exist or did not participate.
+    ///
+    /// This is the offsets-only counterpart to [`Captures::get`]: the range is relative to the
+    /// start of the match, so it can index the matched slice or, shifted by the match offset,
+    /// the original buffer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use winnow::prelude::*;
+    /// use winnow_regex::captures_ctx;
+    ///
+    /// let caps = captures_ctx(r"^(\w+)=(\d+)").parse_next(&mut "key=42;").unwrap();
+    /// assert_eq!(caps.range(2), Some(4..6));
+    /// assert_eq!(caps.range(3), None);
+    /// ```
+    pub fn range(&self, i: usize) -> Option<Range<usize>> {
+        let (start, end) = self.span(i)?;
+        Some(start..end)
+    }
+
+    /// Returns the byte range of the group named `name` within the matched slice, or `None` if
+    /// there is no such group or it did not participate.
+    ///
+    /// This is [`Captures::range`] keyed by group name, e.g. to point a diagnostic at one field
+    /// of the match.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use winnow::prelude::*;
+    /// use winnow_regex::captures_ctx;
+    ///
+    /// let caps = captures_ctx(r"^(?<year>\d{4})-(?<month>\d{2})").parse_next(&mut "2024-13").unwrap();
+    /// assert_eq!(caps.name_range("month"), Some(5..7));
+    /// assert_eq!(caps.name_range("day"), None);
+    /// ```
+    pub fn name_range(&self, name: &str) -> Option<Range<usize>> {
+        let i = self
+            .names
+            .iter()
+            .position(|group| group.as_deref() == Some(name))?;
+        self.range(i)
+    }
+
+    /// Iterates over the named groups in pattern order, pairing each name with its range
+    /// within the matched slice, or `None` if the group did not participate.
+    ///
+    /// Shifted by the match offset, e.g. with [`RichMatch::absolute_range`] or the start of a
+    /// [`LocatingSlice`], these give highlight spans keyed by the group names.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use winnow::prelude::*;
+    /// use winnow_regex::captures_ctx;
+    ///
+    /// let caps = captures_ctx(r"^(?<key>\w+)(\s*)=(?<value>\d+)?").parse_next(&mut "w =;").unwrap();
+    /// let ranges: Vec<_> = caps.named_ranges().collect();
+    /// assert_eq!(ranges, [("key", Some(0..1)), ("value", None)]);
+    /// ```
+    pub fn named_ranges(&self) -> impl Iterator<Item = (&str, Option<Range<usize>>)> {
+        self.names
+            .iter()
+            .enumerate()
+            .filter_map(|(i, name)| Some((name.as_deref()?, self.range(i))))
+    }
+}
+
+impl<Slice, L> Captures<Slice, L>
+where
+    Slice: AsRef<L::Input>,
+    L: CaptureLocations,
+    L::Input: Index<Range<usize>, Output = L::Input>,
+{
+    /// Returns the text of group `i`, or `None` if it does not exist or did not participate.
+    pub fn get(&self, i: usize) -> Option<&L::Input> {
+        let (start, end) = self.span(i)?;
+        Some(&self.slice.as_ref()[start..end])
+    }
+
+    /// Returns the text of group `i`, or a [`CaptureError`] saying why there is none.
+    ///
+    /// This is the fallible form of indexing: unlike [`Captures::get`], the error tells a group
+    /// index past the end of the pattern apart from a group that did not participate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use winnow::prelude::*;
+    /// use winnow_regex::{CaptureError, captures_ctx};
+    ///
+    /// let caps = captures_ctx(r"^(\w+)(?:=(\d+))?").parse_next(&mut "key;").unwrap();
+    /// assert_eq!(caps.try_index(1), Ok("key"));
+    /// assert_eq!(caps.try_index(2), Err(CaptureError::NotParticipating { index: 2 }));
+    /// assert_eq!(caps.try_index(3), Err(CaptureError::OutOfRange { index: 3, len: 3 }));
+    /// ```
+    pub fn try_index(&self, i: usize) -> Result<&L::Input, CaptureError> {
+        if i >= self.len() {
+            return Err(CaptureError::OutOfRange {
+                index: i,
+                len: self.len(),
+            });
+        }
+        self.get(i)
+            .ok_or(CaptureError::NotParticipating { index: i })
+    }
+
+    /// Iterates over all groups in pattern order, starting with group 0 for the whole match.
+    ///
+    /// Groups that did not participate in the match yield `None`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use winnow::prelude::*;
+    /// use winnow_regex::captures_ctx;
+    ///
+    /// let caps = captures_ctx(r"^(\w+)(?:=(\d+))?").parse_next(&mut "key;").unwrap();
+    /// let groups: Vec<_> = caps.iter().collect();
+    /// assert_eq!(groups, [Some("key"), Some("key"), None]);
+    /// ```
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = Option<&L::Input>> {
+        (0..self.len()).map(|i| self.get(i))
+    }
+
+    /// Iterates over the named groups in the order they appear in the pattern.
+    ///
+    /// Each item pairs the group name with its text, or `None` if the group did not
+    /// participate in the match. Unnamed groups are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use winnow::prelude::*;
+    /// use winnow_regex::captures_ctx;
+    ///
+    /// let caps = captures_ctx(r"^(?<y>\d{4})-(\d{2})(?:-(?<d>\d{2}))?")
+    ///     .parse_next(&mut "2024-05")
+    ///     .unwrap();
+    /// let named: Vec<_> = caps.named_iter().collect();
+    /// assert_eq!(named, [("y", Some("2024")), ("d", None)]);
+    /// ```
+    pub fn named_iter(&self) -> impl Iterator<Item = (&str, Option<&L::Input>)> {
+        self.names
+            .iter()
+            .enumerate()
+            .filter_map(|(i, name)| Some((name.as_deref()?, self.get(i))))
+    }
+
+    /// Returns the text of the explicit groups as an array, for destructuring.
+    ///
+    /// Group 0 is left out, so `N` must equal the number of groups in the pattern. Returns
+    /// `None` if it does not, or if any of the groups did not participate in the match. Works
+    /// the same on byte streams, giving `[&[u8]; N]`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use winnow::prelude::*;
+    /// use winnow_regex::captures_ctx;
+    ///
+    /// let caps = captures_ctx(r"^(\w+)=(\d+)").parse_next(&mut "key=42;").unwrap();
+    /// let [key, value] = caps.to_array().unwrap();
+    /// assert_eq!((key, value), ("key", "42"));
+    /// assert_eq!(caps.to_array::<3>(), None);
+    /// ```
+    pub fn to_array<const N: usize>(&self) -> Option<[&L::Input; N]> {
+        if self.len() != N + 1 {
+            return None;
+        }
+        let groups = (1..=N).map(|i| self.get(i)).collect::<Option<Vec<_>>>()?;
+        groups.try_into().ok()
+    }
+
+    /// Returns the literal text between consecutive capture groups, in position order.
+    ///
+    /// Participating groups other than group 0 are ordered by where they start, and each gap
+    /// runs from the end of one group to the start of the next, so `n` groups give `n - 1`
+    /// gaps. A group nested inside another is covered by the enclosing group and does not
+    /// produce gaps of its own. Text before the first group and after the last one is not
+    /// included.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use winnow::prelude::*;
+    /// use winnow_regex::captures_ctx;
+    ///
+    /// let caps = captures_ctx(r"^(\d+)\s*x\s*(\d+)(?:@(\d+))?(px)")
+    ///     .parse_next(&mut "800 x 600px")
+    ///     .unwrap();
+    /// assert_eq!(caps.gaps(), [" x ", ""]);
+    /// ```
+    pub fn gaps(&self) -> Vec<&L::Input> {
+        let mut spans: Vec<_> = (1..self.len()).filter_map(|i| self.span(i)).collect();
+        // Outer groups sort before the groups they contain.
+        spans.sort_by_key(|&(start, end)| (start, core::cmp::Reverse(end)));
+
+        let hay = self.slice.as_ref();
+        let mut gaps = Vec::new();
+        let mut prev_end = None;
+        for (start, end) in spans {
+            match prev_end {
+                Some(prev) if start < prev => continue,
+                Some(prev) => gaps.push(&hay[prev..start]),
+                None => {}
+            }
+            prev_end = Some(end);
+        }
+        gaps
+    }
+
+    /// Formats every group on its own line for debugging.
+    ///
+    /// Each line shows the group index, its name if it has one, and its text, or `None` if
+    /// the group did not participate. The format is meant for reading and may change.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use winnow::prelude::*;
+    /// use winnow_regex::captures_ctx;
+    ///
+    /// let caps = captures_ctx(r"^(?<key>\w+)=(\d+)(?<unit>px)?").parse_next(&mut "w=80").unwrap();
+    /// assert_eq!(
+    ///     caps.debug_dump(),
+    ///     "#0: \"w=80\"\n#1 key: \"w\"\n#2: \"80\"\n#3 unit: None\n"
+    /// );
+    /// ```
+    pub fn debug_dump(&self) -> String
+    where
+        L::Input: Debug,
+    {
+        use core::fmt::Write;
+
+        let mut dump = String::new();
+        for (i, name) in self.names.iter().enumerate() {
+            let _ = write!(dump, "#{i}");
+            if let Some(name) = name {
+                let _ = write!(dump, " {name}");
+            }
+            let _ = match self.get(i) {
+                Some(text) => writeln!(dump, ": {text:?}"),
+                None => writeln!(dump, ": None"),
+            };
+        }
+        dump
+    }
+
+    /// Copies the match into an [`OwnedCaptures`] that no longer borrows the input.
+    pub fn to_owned_captures(&self) -> OwnedCaptures<L::Input>
+    where
+        L::Input: ToOwned,
+    {
+        OwnedCaptures {
+            text: self.slice.as_ref().to_owned(),
+            spans: (0..self.len()).map(|i| self.span(i)).collect(),
+            names: self.names.clone(),
+        }
+    }
 }
 
 impl<Slice, L, T: ?Sized> AsRef<T> for Captures<Slice, L>
@@ -120,219 +438,7637 @@ where
     type Output = L::Input;
 
     fn index(&self, i: usize) -> &Self::Output {
-        let (start, end) = self.locs.get(i).expect("index out of bounds");
-        &self.slice.as_ref()[start..end]
+        self.get(i).expect("index out of bounds")
     }
 }
 
-pub struct RegexParser<'h, I, R, E>
+impl<Slice, L> Captures<Slice, L>
 where
-    I: Stream + StreamIsPartial + Offset + Clone,
-    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
-    E: ParserError<I>,
+    Slice: AsRef<str>,
+    L: CaptureLocations<Input = str>,
 {
-    re: R,
-    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+    /// Returns the whole match with the listed groups replaced by new text.
+    ///
+    /// Everything outside the replaced groups, including the [`Captures::gaps`] between them,
+    /// is kept as matched. Groups that do not exist or did not participate are skipped. When
+    /// replaced groups overlap, the one starting first wins and an outer group wins over the
+    /// groups nested in it; for a group listed twice, the first entry wins.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use winnow::prelude::*;
+    /// use winnow_regex::captures_ctx;
+    ///
+    /// let caps = captures_ctx(r"^(?<w>\d+) x (?<h>\d+)").parse_next(&mut "800 x 600;").unwrap();
+    /// assert_eq!(caps.rebuild(&[(1, "1024"), (2, "768")]), "1024 x 768");
+    /// ```
+    pub fn rebuild(&self, replacements: &[(usize, &str)]) -> String {
+        let mut spans: Vec<_> = replacements
+            .iter()
+            .filter_map(|&(i, text)| Some((self.span(i)?, text)))
+            .collect();
+        // Outer groups sort before the groups they contain; the sort is stable, so the first
+        // entry for a group stays first.
+        spans.sort_by_key(|&((start, end), _)| (start, core::cmp::Reverse(end)));
+        spans.dedup_by_key(|&mut (span, _)| span);
+
+        let hay = self.slice.as_ref();
+        let mut rebuilt = String::with_capacity(hay.len());
+        let mut cursor = 0;
+        for ((start, end), text) in spans {
+            if start < cursor {
+                continue;
+            }
+            rebuilt.push_str(&hay[cursor..start]);
+            rebuilt.push_str(text);
+            cursor = end;
+        }
+        rebuilt.push_str(&hay[cursor..]);
+        rebuilt
+    }
 }
 
-impl<'h, I, R, E> Parser<I, <I as Stream>::Slice, E> for RegexParser<'h, I, R, E>
+/// Why [`Captures::try_index`] could not return a group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum CaptureError {
+    /// The pattern has no group with this index.
+    #[error("capture group {index} does not exist, the pattern has {len} groups")]
+    OutOfRange {
+        /// The requested group.
+        index: usize,
+        /// The number of groups in the pattern, including group 0.
+        len: usize,
+    },
+    /// The group exists but did not participate in the match.
+    #[error("capture group {index} did not participate in the match")]
+    NotParticipating {
+        /// The requested group.
+        index: usize,
+    },
+}
+
+/// Captures that own the matched text, so they can outlive the input.
+///
+/// Produced by [`captures_owned`] or [`Captures::to_owned_captures`]. The group structure and
+/// names are kept, and the accessors mirror [`Captures`]: ranges are relative to the match.
+pub struct OwnedCaptures<H>
 where
-    I: Stream + StreamIsPartial + Offset + Clone,
-    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
-    E: ParserError<I>,
+    H: ?Sized + ToOwned,
 {
-    fn parse_next(&mut self, input: &mut I) -> Result<<I as Stream>::Slice, E> {
-        if <I as StreamIsPartial>::is_partial_supported() {
-            captures_impl::<_, _, _, true>(input, &self.re)
-        } else {
-            captures_impl::<_, _, _, false>(input, &self.re)
-        }
-        .map(|caps| caps.slice)
-    }
+    text: H::Owned,
+    spans: Box<[Option<(usize, usize)>]>,
+    names: CaptureNames,
 }
 
-pub struct CapturesParser<'h, I, R, E>
+#[allow(clippy::len_without_is_empty)]
+impl<H> OwnedCaptures<H>
 where
-    I: Stream,
-    R: Regex,
-    E: ParserError<I>,
+    H: ?Sized + ToOwned + Index<Range<usize>, Output = H>,
 {
-    re: R,
-    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+    /// See [`Captures::len`].
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    /// See [`Captures::range`].
+    pub fn range(&self, i: usize) -> Option<Range<usize>> {
+        let (start, end) = (*self.spans.get(i)?)?;
+        Some(start..end)
+    }
+
+    /// Returns the text of the whole match.
+    pub fn as_match(&self) -> &H {
+        self.text.borrow()
+    }
+
+    /// Consumes the captures, returning the owned text of the whole match.
+    pub fn into_match(self) -> H::Owned {
+        self.text
+    }
+
+    /// See [`Captures::get`].
+    pub fn get(&self, i: usize) -> Option<&H> {
+        Some(&self.as_match()[self.range(i)?])
+    }
+
+    /// See [`Captures::named_iter`].
+    pub fn named_iter(&self) -> impl Iterator<Item = (&str, Option<&H>)> {
+        self.names
+            .iter()
+            .enumerate()
+            .filter_map(|(i, name)| Some((name.as_deref()?, self.get(i))))
+    }
 }
 
-impl<'h, I, R, E> Parser<I, Captures<<I as Stream>::Slice, R::CaptureLocations>, E>
-    for CapturesParser<'h, I, R, E>
+impl<H> Clone for OwnedCaptures<H>
 where
-    I: Stream + StreamIsPartial + Offset + Clone,
-    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
-    E: ParserError<I>,
+    H: ?Sized + ToOwned,
 {
-    fn parse_next(
-        &mut self,
-        input: &mut I,
-    ) -> Result<Captures<<I as Stream>::Slice, R::CaptureLocations>, E> {
-        if <I as StreamIsPartial>::is_partial_supported() {
-            captures_impl::<_, _, _, true>(input, &self.re)
-        } else {
-            captures_impl::<_, _, _, false>(input, &self.re)
+    fn clone(&self) -> Self {
+        Self {
+            text: self.text.borrow().to_owned(),
+            spans: self.spans.clone(),
+            names: self.names.clone(),
         }
     }
 }
 
-/// Creates a parser that matches input using a regular expression.
-///
-/// This parser takes a regular expression pattern (implementing [`RegexPattern`])
-/// and returns a parser that attempts to match from the **beginning** of the input.
-/// If the regular expression does not match at position 0, the parser fails.
-///
-/// Internally, this uses a precompiled [`Regex`] from the [`regex`] crate and supports
-/// both complete and partial input modes via the [`StreamIsPartial`] trait.
-///
-/// # Panics
-///
-/// Panics if the regex pattern fails to compile.
-///
-/// # Example
-///
-/// ```
-/// use winnow::prelude::*;
-/// use winnow_regex::regex;
-///
-/// fn digits<'i>(s: &mut &'i str) -> ModalResult<&'i str> {
-///     regex(r"^\d+").parse_next(s)
-/// }
-///
-/// assert_eq!(digits.parse_peek("42abc"), Ok(("abc", "42")));
-/// assert!(digits.parse_peek("abc42").is_err());
-///
-/// // Example with precompiled regex
-/// fn word<'i>(s: &mut &'i str) -> ModalResult<&'i str> {
-///     let re = regex::Regex::new(r"^\w+").unwrap();
-///     regex(re).parse_next(s)
-/// }
-///
-/// assert_eq!(word.parse_peek("hello world"), Ok((" world", "hello")));
-/// assert!(word.parse_peek("!hello").is_err());
-/// ```
-#[inline(always)]
-pub fn regex<'h, Input, Re, Error>(re: Re) -> RegexParser<'h, Input, Re::Output, Error>
+impl<H> core::ops::Index<usize> for OwnedCaptures<H>
 where
-    Input: StreamIsPartial + Stream + Offset + Clone,
-    Re: RegexPattern,
-    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
-    Re::Error: Debug,
-    Error: ParserError<Input> + 'static,
+    H: ?Sized + ToOwned + Index<Range<usize>, Output = H>,
 {
-    let re = re.into_regex();
+    type Output = H;
 
-    RegexParser {
-        re,
-        _marker: core::marker::PhantomData,
+    fn index(&self, i: usize) -> &Self::Output {
+        self.get(i).expect("index out of bounds")
     }
 }
 
-/// # Example
-/// ```
-/// use winnow::prelude::*;
-/// use winnow_regex::{captures, Captures};
-///
-/// fn digits<'i>(s: &mut &'i str) -> ModalResult<(i32, i32)> {
-///    captures(r"^(\d+)x(\d+)").map(|c: Captures<&str, _>| (c[1].parse().unwrap(), c[2].parse().unwrap())).parse_next(s)
-/// }
+/// A match bundling its [`Captures`] with its absolute position in the input.
 ///
-/// assert_eq!(digits.parse_peek("11x42abc"), Ok(("abc", (11, 42))));
-/// ```
-#[inline(always)]
-pub fn captures<'h, Input, Re, Error>(re: Re) -> CapturesParser<'h, Input, Re::Output, Error>
+/// Produced by [`rich_captures`] and [`find_iter`]. Group accessors mirror [`Captures`] and report ranges relative
+/// to the match; the `absolute_*` variants add the match offset.
+pub struct RichMatch<Slice, L>
 where
-    Input: StreamIsPartial + Stream + Offset + Clone,
-    Re: RegexPattern,
-    Re::Output: Regex,
-    Re::Error: Debug,
-    Error: ParserError<Input> + 'static,
+    L: CaptureLocations,
 {
-    let re = re.into_regex();
-
-    CapturesParser {
-        re,
-        _marker: core::marker::PhantomData,
-    }
+    caps: Captures<Slice, L>,
+    offset: usize,
 }
 
-fn captures_impl<'h, I, Re, E, const PARTIAL: bool>(
-    input: &mut I,
-    re: &Re,
-) -> Result<Captures<<I as Stream>::Slice, Re::CaptureLocations>, E>
+#[allow(clippy::len_without_is_empty)]
+impl<Slice, L> RichMatch<Slice, L>
 where
-    I: Stream + StreamIsPartial + Offset + Clone,
-    Re: Regex<Haystack<'h> = <I as Stream>::Slice>,
-    E: ParserError<I>,
+    L: CaptureLocations,
 {
-    let hay = input.peek_finish();
-    let mut locs = re.capture_locations();
+    /// Returns the absolute offset of the start of the match.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
 
-    match re.captures_read(&mut locs, hay) {
-        Some((start, end)) if start == 0 => {
-            let len = end;
-            if PARTIAL && input.is_partial() && input.eof_offset() == end {
-                Err(E::incomplete(input, Needed::Unknown))
-            } else {
-                Ok(Captures {
-                    slice: input.next_slice(len),
-                    locs,
-                })
-            }
-        }
-        _ if PARTIAL && input.is_partial() => Err(E::incomplete(input, Needed::Unknown)),
-        _ => Err(ParserError::from_input(input)),
+    /// Returns the absolute byte range of the whole match.
+    pub fn span(&self) -> Range<usize> {
+        self.absolute_range(0)
+            .expect("group 0 always participates in a match")
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use winnow::error::{ContextError, EmptyError, ErrMode};
-    use winnow::prelude::*;
+    /// See [`Captures::len`].
+    pub fn len(&self) -> usize {
+        self.caps.len()
+    }
 
-    #[test]
-    fn regex_parser() {
-        let mut p: RegexParser<&str, regex::Regex, EmptyError> = RegexParser {
-            re: regex::Regex::new(r"^\d+").unwrap(),
-            _marker: core::marker::PhantomData,
-        };
-        assert_eq!(p.parse_peek("42abc"), Ok(("abc", "42")));
+    /// See [`Captures::range`].
+    pub fn range(&self, i: usize) -> Option<Range<usize>> {
+        self.caps.range(i)
     }
 
-    #[test]
-    fn ok_with_literal_pattern() {
-        fn digits<'i>(s: &mut &'i str) -> ModalResult<&'i str> {
-            regex(r"^\d+").parse_next(s)
-        }
-        assert_eq!(digits.parse_peek("42xyz"), Ok(("xyz", "42")));
+    /// Returns the absolute byte range of group `i`, or `None` if it does not exist or did not
+    /// participate.
+    pub fn absolute_range(&self, i: usize) -> Option<Range<usize>> {
+        let range = self.caps.range(i)?;
+        Some(self.offset + range.start..self.offset + range.end)
     }
 
-    #[test]
-    fn unicode_partial() {
-        let mut s = "あいう123";
-        let re = regex::<_, _, EmptyError>(r"^[ぁ-ん]+")
+    /// Returns the underlying captures.
+    pub fn captures(&self) -> &Captures<Slice, L> {
+        &self.caps
+    }
+
+    /// Consumes the match, returning the underlying captures.
+    pub fn into_captures(self) -> Captures<Slice, L> {
+        self.caps
+    }
+}
+
+impl<Slice, L> RichMatch<Slice, L>
+where
+    Slice: AsRef<L::Input>,
+    L: CaptureLocations,
+    L::Input: Index<Range<usize>, Output = L::Input>,
+{
+    /// Returns the text of the whole match.
+    pub fn as_match(&self) -> &L::Input {
+        self.caps.slice.as_ref()
+    }
+
+    /// See [`Captures::get`].
+    pub fn get(&self, i: usize) -> Option<&L::Input> {
+        self.caps.get(i)
+    }
+
+    /// See [`Captures::named_iter`].
+    pub fn named_iter(&self) -> impl Iterator<Item = (&str, Option<&L::Input>)> {
+        self.caps.named_iter()
+    }
+}
+
+impl<Slice, L> core::ops::Index<usize> for RichMatch<Slice, L>
+where
+    Slice: AsRef<L::Input>,
+    L: CaptureLocations,
+    L::Input: Index<Range<usize>, Output = L::Input>,
+{
+    type Output = L::Input;
+
+    fn index(&self, i: usize) -> &Self::Output {
+        &self.caps[i]
+    }
+}
+
+/// What an [`EmptyMatchParser`] does when the regex matches the empty string at the start of
+/// the input.
+///
+/// Set with [`RegexParser::empty_match`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EmptyMatch {
+    /// Succeed with an empty slice, consuming nothing. This is the default.
+    #[default]
+    Accept,
+    /// Fail like a non-matching regex, so combinators such as `repeat` or `alt` backtrack.
+    Reject,
+    /// Fail with an [`EmptyMatchError`] attached through [`FromExternalError`], for grammars
+    /// where an empty match is a bug.
+    Error,
+}
+
+/// The error attached by [`EmptyMatch::Error`] when the regex matches the empty string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("regex must not match the empty string")]
+pub struct EmptyMatchError;
+
+pub struct RegexParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    re: R,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> RegexParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    /// Wraps the parser to treat a match of the empty string as `mode` says; a plain
+    /// [`RegexParser`] accepts it.
+    ///
+    /// A pattern such as `^\d*` matches every input, so inside `repeat` it would succeed
+    /// without consuming anything; winnow catches that as an infinite loop. Choosing
+    /// [`EmptyMatch::Reject`] makes the repetition stop instead, and [`EmptyMatch::Error`]
+    /// reports it as a bug in the grammar.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use winnow::combinator::repeat;
+    /// use winnow::prelude::*;
+    /// use winnow_regex::{EmptyMatch, regex};
+    ///
+    /// fn numbers(s: &mut &str) -> ModalResult<Vec<String>> {
+    ///     repeat(0.., regex(r"^\d*,?").empty_match(EmptyMatch::Reject).map(str::to_owned))
+    ///         .parse_next(s)
+    /// }
+    ///
+    /// assert_eq!(numbers.parse_peek("1,22;"), Ok((";", vec!["1,".to_owned(), "22".to_owned()])));
+    /// ```
+    pub fn empty_match(self, mode: EmptyMatch) -> EmptyMatchParser<'h, I, R, E> {
+        EmptyMatchParser { inner: self, mode }
+    }
+
+    /// Returns the names of the capture groups in pattern order, `None` for unnamed groups.
+    ///
+    /// The first item is group 0, the whole match, which never has a name. This lets generic
+    /// tooling discover the groups of a pattern it did not write.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use winnow_regex::regex_ctx;
+    ///
+    /// let p = regex_ctx::<&str, _>(r"^(?<key>\w+)=(\d+)");
+    /// assert_eq!(p.capture_names().collect::<Vec<_>>(), [None, Some("key"), None]);
+    /// ```
+    pub fn capture_names(&self) -> impl Iterator<Item = Option<&str>> {
+        self.re.capture_names()
+    }
+}
+
+impl<'h, I, R, E> Clone for RegexParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice> + Clone,
+    E: ParserError<I>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            re: self.re.clone(),
+            _marker: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<'h, I, R, E> Parser<I, <I as Stream>::Slice, E> for RegexParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<<I as Stream>::Slice, E> {
+        if <I as StreamIsPartial>::is_partial_supported() {
+            captures_impl::<_, _, _, true>(input, &self.re)
+        } else {
+            captures_impl::<_, _, _, false>(input, &self.re)
+        }
+        .map(|(slice, _)| slice)
+    }
+}
+
+pub struct EmptyMatchParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    inner: RegexParser<'h, I, R, E>,
+    mode: EmptyMatch,
+}
+
+impl<'h, I, R, E> Clone for EmptyMatchParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice> + Clone,
+    E: ParserError<I>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            mode: self.mode,
+        }
+    }
+}
+
+impl<'h, I, R, E> Parser<I, <I as Stream>::Slice, E> for EmptyMatchParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I> + FromExternalError<I, EmptyMatchError>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<<I as Stream>::Slice, E> {
+        let start = input.checkpoint();
+        let slice = self.inner.parse_next(input)?;
+
+        match self.mode {
+            EmptyMatch::Reject if input.offset_from(&start) == 0 => {
+                Err(ParserError::from_input(input))
+            }
+            EmptyMatch::Error if input.offset_from(&start) == 0 => {
+                Err(E::from_external_error(input, EmptyMatchError))
+            }
+            _ => Ok(slice),
+        }
+    }
+}
+
+pub struct CapturesParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    names: CaptureNames,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> CapturesParser<'h, I, R, E>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    /// Returns the names of the capture groups in pattern order, like
+    /// [`RegexParser::capture_names`].
+    pub fn capture_names(&self) -> impl ExactSizeIterator<Item = Option<&str>> {
+        self.names.iter().map(Option::as_deref)
+    }
+}
+
+impl<'h, I, R, E> Clone for CapturesParser<'h, I, R, E>
+where
+    I: Stream,
+    R: Regex + Clone,
+    E: ParserError<I>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            re: self.re.clone(),
+            names: self.names.clone(),
+            _marker: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<'h, I, R, E> Parser<I, Captures<<I as Stream>::Slice, R::CaptureLocations>, E>
+    for CapturesParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(
+        &mut self,
+        input: &mut I,
+    ) -> Result<Captures<<I as Stream>::Slice, R::CaptureLocations>, E> {
+        let (slice, locs) = if <I as StreamIsPartial>::is_partial_supported() {
+            captures_impl::<_, _, _, true>(input, &self.re)
+        } else {
+            captures_impl::<_, _, _, false>(input, &self.re)
+        }?;
+        Ok(Captures {
+            slice,
+            locs,
+            names: self.names.clone(),
+            base: 0,
+        })
+    }
+}
+
+/// Creates a parser that matches input using a regular expression.
+///
+/// This parser takes a regular expression pattern (implementing [`RegexPattern`])
+/// and returns a parser that attempts to match from the **beginning** of the input.
+/// If the regular expression does not match at position 0, the parser fails.
+///
+/// Internally, this uses a precompiled [`Regex`] from the [`regex()`] crate and supports
+/// both complete and partial input modes via the [`StreamIsPartial`] trait.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::regex;
+///
+/// fn digits<'i>(s: &mut &'i str) -> ModalResult<&'i str> {
+///     regex(r"^\d+").parse_next(s)
+/// }
+///
+/// assert_eq!(digits.parse_peek("42abc"), Ok(("abc", "42")));
+/// assert!(digits.parse_peek("abc42").is_err());
+///
+/// // Example with precompiled regex
+/// fn word<'i>(s: &mut &'i str) -> ModalResult<&'i str> {
+///     let re = regex::Regex::new(r"^\w+").unwrap();
+///     regex(re).parse_next(s)
+/// }
+///
+/// assert_eq!(word.parse_peek("hello world"), Ok((" world", "hello")));
+/// assert!(word.parse_peek("!hello").is_err());
+/// ```
+#[inline(always)]
+pub fn regex<'h, Input, Re, Error>(re: Re) -> RegexParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    let re = re.into_regex();
+
+    RegexParser {
+        re,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+/// # Example
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::{captures, Captures};
+///
+/// fn digits<'i>(s: &mut &'i str) -> ModalResult<(i32, i32)> {
+///    captures(r"^(\d+)x(\d+)").map(|c: Captures<&str, _>| (c[1].parse().unwrap(), c[2].parse().unwrap())).parse_next(s)
+/// }
+///
+/// assert_eq!(digits.parse_peek("11x42abc"), Ok(("abc", (11, 42))));
+/// ```
+///
+/// # Reusing the parser
+///
+/// Each call compiles the pattern, so calling `captures` inside a function that runs many times
+/// recompiles it every time. Build the parser once instead and let winnow call `parse_next` on
+/// it repeatedly; [`CapturesParser`] is also [`Clone`] when the regex is, so it can be stored
+/// and handed out cheaply.
+///
+/// ```
+/// use winnow::combinator::repeat;
+/// use winnow::prelude::*;
+/// use winnow_regex::captures;
+///
+/// fn pairs(s: &mut &str) -> ModalResult<Vec<(String, u32)>> {
+///     // Compiled once per call to `pairs`, not once per pair.
+///     let pair = captures(r"^(\w)=(\d);").map(|c| (c[1].to_owned(), c[2].parse().unwrap()));
+///     repeat(0.., pair).parse_next(s)
+/// }
+///
+/// assert_eq!(pairs.parse_peek("a=1;b=2;c=3;").unwrap().1.len(), 3);
+/// ```
+///
+/// # Tracking positions
+///
+/// Wrapping the input in [`winnow::stream::LocatingSlice`] (called
+/// `Located` in earlier winnow versions) works with every parser in this crate, since the
+/// slices it produces are the same as those of the wrapped input. The
+/// [`winnow::stream::Location`] of the stream then gives the offset of each match;
+/// see also [`rich_captures`].
+///
+/// ```
+/// use winnow::ascii::multispace0;
+/// use winnow::prelude::*;
+/// use winnow::stream::{LocatingSlice, Location};
+/// use winnow_regex::captures;
+///
+/// fn field(s: &mut LocatingSlice<&str>) -> ModalResult<(usize, String)> {
+///     multispace0.parse_next(s)?;
+///     let start = s.current_token_start();
+///     let caps = captures(r"^(\w+);").parse_next(s)?;
+///     Ok((start, caps[1].to_owned()))
+/// }
+///
+/// let mut input = LocatingSlice::new("  name; x");
+/// assert_eq!(field.parse_next(&mut input), Ok((2, "name".to_owned())));
+/// assert_eq!(input.current_token_start(), 7);
+/// ```
+#[inline(always)]
+pub fn captures<'h, Input, Re, Error>(re: Re) -> CapturesParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    let re = re.into_regex();
+
+    CapturesParser {
+        names: capture_names(&re),
+        re,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+/// [`regex()`] with the error type fixed to `ErrMode<ContextError>`, the error of [`ModalResult`].
+///
+/// Use this where nothing else pins down the error type, such as calling `parse_next` outside a
+/// function returning [`ModalResult`], to avoid spelling it out with a turbofish.
+///
+/// [`ModalResult`]: winnow::ModalResult
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::regex_ctx;
+///
+/// let mut input = "42abc";
+/// assert_eq!(regex_ctx(r"^\d+").parse_next(&mut input), Ok("42"));
+/// assert_eq!(input, "abc");
+/// ```
+#[inline(always)]
+pub fn regex_ctx<'h, Input, Re>(re: Re) -> RegexParser<'h, Input, Re::Output>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+{
+    regex(re)
+}
+
+/// [`captures`] with the error type fixed to `ErrMode<ContextError>`, the error of
+/// [`ModalResult`].
+///
+/// [`ModalResult`]: winnow::ModalResult
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+#[inline(always)]
+pub fn captures_ctx<'h, Input, Re>(re: Re) -> CapturesParser<'h, Input, Re::Output>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex,
+    Re::Error: Debug,
+{
+    captures(re)
+}
+
+/// Creates a parser that only succeeds if `pattern` matches the whole remaining input.
+///
+/// The pattern is wrapped as `^(?:pattern)\z`, so it is anchored at both ends and the entire
+/// remaining input is returned. This is the usual way to check that a field is exactly of a
+/// given form, e.g. after isolating it with another parser.
+///
+/// On partial input this always returns `Incomplete`, as the end of the input is not known
+/// yet.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::regex_full;
+///
+/// fn date<'i>(s: &mut &'i str) -> ModalResult<&'i str> {
+///     regex_full(r"\d{4}-\d{2}-\d{2}").parse_next(s)
+/// }
+///
+/// assert_eq!(date.parse_peek("2024-05-17"), Ok(("", "2024-05-17")));
+/// assert!(date.parse_peek("2024-05-17T00:00").is_err());
+/// ```
+#[inline(always)]
+pub fn regex_full<'h, Input, Error>(pattern: &str) -> RegexParser<'h, Input, regex::Regex, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    regex::Regex: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Error: ParserError<Input> + 'static,
+{
+    regex(format!(r"^(?:{pattern})\z"))
+}
+
+/// Creates a [`regex()`] parser from a configured [`regex::RegexBuilder`].
+///
+/// Every option of the `regex` crate's builder is available this way, including ones
+/// [`RegexBuilder`] does not mirror. The builder is only borrowed, so it can be reused to build
+/// several parsers.
+///
+/// # Errors
+///
+/// Returns an error if the regex fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::regex_from_builder;
+///
+/// fn word<'i>(s: &mut &'i str) -> ModalResult<&'i str> {
+///     let mut builder = regex::RegexBuilder::new(r"^hello");
+///     builder.case_insensitive(true);
+///     regex_from_builder(&builder).unwrap().parse_next(s)
+/// }
+///
+/// assert_eq!(word.parse_peek("HeLLo world"), Ok((" world", "HeLLo")));
+/// ```
+#[inline(always)]
+pub fn regex_from_builder<'h, Input, Error>(
+    builder: &regex::RegexBuilder,
+) -> Result<RegexParser<'h, Input, regex::Regex, Error>, crate::Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    regex::Regex: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Error: ParserError<Input> + 'static,
+{
+    Ok(regex(builder.build()?))
+}
+
+/// Like [`regex()`], but wrapped in winnow's [`trace`](winnow::combinator::trace) for debugging.
+///
+/// The trace is labelled `name`, or `regex(<pattern>)` when `name` is `None`, so the output
+/// shows which regex fired during a larger parse. Tracing only prints anything when winnow's
+/// `debug` feature is enabled; otherwise this behaves exactly like [`regex()`].
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::traced_regex;
+///
+/// fn digits<'i>(s: &mut &'i str) -> ModalResult<&'i str> {
+///     traced_regex(None, r"^\d+").parse_next(s)
+/// }
+///
+/// assert_eq!(digits.parse_peek("42abc"), Ok(("abc", "42")));
+/// ```
+#[inline(always)]
+pub fn traced_regex<'h, Input, Re, Error>(
+    name: Option<&str>,
+    re: Re,
+) -> impl Parser<Input, <Input as Stream>::Slice, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    let parser = regex(re);
+    winnow::combinator::trace(trace_name(name, &parser.re), parser)
+}
+
+/// Returns the label for [`traced_regex`], falling back to one built from the pattern.
+fn trace_name<R: Regex>(name: Option<&str>, re: &R) -> String {
+    match name {
+        Some(name) => name.to_owned(),
+        None => format!("regex({})", re.as_str()),
+    }
+}
+
+pub struct WithPatternParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    pattern: Arc<str>,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> Parser<I, (<I as Stream>::Slice, Arc<str>), E> for WithPatternParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<(<I as Stream>::Slice, Arc<str>), E> {
+        let found = match self.re.find(input.peek_finish()) {
+            Some((0, end)) => Some((end, ())),
+            _ => None,
+        };
+        let (slice, _) = if <I as StreamIsPartial>::is_partial_supported() {
+            consume_match::<_, _, E, true>(input, found)
+        } else {
+            consume_match::<_, _, E, false>(input, found)
+        }?;
+        Ok((slice, self.pattern.clone()))
+    }
+}
+
+/// Like [`regex()`], but also returns the pattern of the regex, for recording which one matched.
+///
+/// The pattern comes from [`Regex::pattern`] and is shared, so returning it does not copy the
+/// string. This helps when parsers are built from dynamic patterns and an audit log should say
+/// which of them accepted the input. Regexes that do not know their pattern return an empty
+/// string.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::combinator::alt;
+/// use winnow::prelude::*;
+/// use winnow_regex::regex_with_pattern;
+///
+/// fn token<'i>(s: &mut &'i str) -> ModalResult<(&'i str, std::sync::Arc<str>)> {
+///     alt((regex_with_pattern(r"^\d+"), regex_with_pattern(r"^\w+"))).parse_next(s)
+/// }
+///
+/// let (_, (text, pattern)) = token.parse_peek("abc").unwrap();
+/// assert_eq!((text, &*pattern), ("abc", r"^\w+"));
+/// ```
+#[inline(always)]
+pub fn regex_with_pattern<'h, Input, Re, Error>(
+    re: Re,
+) -> WithPatternParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    let re = re.into_regex();
+
+    WithPatternParser {
+        pattern: re.pattern().into(),
+        re,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+pub struct CapturesAtParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    names: CaptureNames,
+    at: usize,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> Parser<I, Captures<<I as Stream>::Slice, R::CaptureLocations>, E>
+    for CapturesAtParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(
+        &mut self,
+        input: &mut I,
+    ) -> Result<Captures<<I as Stream>::Slice, R::CaptureLocations>, E> {
+        let found = if input.eof_offset() < self.at {
+            None
+        } else {
+            let mut locs = self.re.capture_locations();
+            match self
+                .re
+                .captures_read_at(&mut locs, input.peek_finish(), self.at)
+            {
+                Some((start, end)) if start == self.at => Some((end, locs)),
+                _ => None,
+            }
+        };
+
+        let start = input.checkpoint();
+        let (_, locs) = if <I as StreamIsPartial>::is_partial_supported() {
+            consume_match::<_, _, E, true>(input, found)
+        } else {
+            consume_match::<_, _, E, false>(input, found)
+        }?;
+        let end = input.offset_from(&start);
+        Ok(Captures {
+            slice: slice_at(input, &start, (self.at, end)),
+            locs,
+            names: self.names.clone(),
+            base: self.at,
+        })
+    }
+}
+
+/// Like [`captures`], but matches `at` bytes into the remaining input, after a known prefix.
+///
+/// The regex must match starting exactly at `at`. The prefix and the match are consumed
+/// together, while the returned captures cover the match alone, so group 0 does not include
+/// the prefix and [`Captures::range`] stays relative to the match. The prefix is still visible
+/// to look-around assertions, so `\b` at the start of the pattern sees the character before it.
+///
+/// Because the search starts at `at` rather than at the start of the input, a leading `^` never
+/// matches for `at > 0`; leave the pattern unanchored instead. On a string stream, `at` must
+/// fall on a character boundary.
+///
+/// On partial input, an input shorter than `at`, no match, or a match reaching the end of the
+/// buffer returns `Incomplete`.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::captures_at;
+///
+/// fn header(s: &mut &str) -> ModalResult<(String, String)> {
+///     captures_at(r"(\w+): (\w+)", 4)
+///         .map(|caps| (caps[1].to_owned(), caps[2].to_owned()))
+///         .parse_next(s)
+/// }
+///
+/// let (rest, (name, value)) = header.parse_peek("HDR Host: example\n").unwrap();
+/// assert_eq!((rest, &*name, &*value), ("\n", "Host", "example"));
+/// assert!(header.parse_peek("HDR : x").is_err());
+/// ```
+#[inline(always)]
+pub fn captures_at<'h, Input, Re, Error>(
+    re: Re,
+    at: usize,
+) -> CapturesAtParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    let re = re.into_regex();
+
+    CapturesAtParser {
+        names: capture_names(&re),
+        re,
+        at,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+pub struct MaxLenParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    max: usize,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> Parser<I, <I as Stream>::Slice, E> for MaxLenParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<<I as Stream>::Slice, E> {
+        let found = find_at_start(input, &self.re);
+        if matches!(found, Some((end, _)) if end > self.max) {
+            return Err(ParserError::from_input(input));
+        }
+        if <I as StreamIsPartial>::is_partial_supported() {
+            consume_match::<_, _, _, true>(input, found)
+        } else {
+            consume_match::<_, _, _, false>(input, found)
+        }
+        .map(|(slice, _)| slice)
+    }
+}
+
+/// Like [`regex()`], but fails if the match would be longer than `max` bytes.
+///
+/// The length is checked before anything is consumed, so a greedy pattern such as `^.+` cannot
+/// swallow an arbitrarily large prefix. A match that is too long fails with
+/// [`ParserError::from_input`] at the current position. On partial input the check also happens
+/// before the `Incomplete` decision, so an over-long match fails right away instead of asking
+/// for more data that could only make it longer.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::regex_max_len;
+///
+/// fn short_word<'i>(s: &mut &'i str) -> ModalResult<&'i str> {
+///     regex_max_len(r"^\w+", 5).parse_next(s)
+/// }
+///
+/// assert_eq!(short_word.parse_peek("hello world"), Ok((" world", "hello")));
+/// assert!(short_word.parse_peek("greetings world").is_err());
+/// ```
+#[inline(always)]
+pub fn regex_max_len<'h, Input, Re, Error>(
+    re: Re,
+    max: usize,
+) -> MaxLenParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    let re = re.into_regex();
+
+    MaxLenParser {
+        re,
+        max,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+pub struct SplitAtParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> Parser<I, (<I as Stream>::Slice, <I as Stream>::Slice), E>
+    for SplitAtParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(
+        &mut self,
+        input: &mut I,
+    ) -> Result<(<I as Stream>::Slice, <I as Stream>::Slice), E> {
+        let (matched, _) = if <I as StreamIsPartial>::is_partial_supported() {
+            captures_impl::<_, _, _, true>(input, &self.re)
+        } else {
+            captures_impl::<_, _, _, false>(input, &self.re)
+        }?;
+        Ok((matched, input.peek_finish()))
+    }
+}
+
+/// Creates a parser that returns the match together with everything after it.
+///
+/// The parser behaves like [`regex()`] and consumes only the match. The second element is a view
+/// of the rest of the input that is **not** consumed, which is handy for header/body style
+/// separation where the remainder is inspected but parsed later.
+///
+/// Both slices borrow from the same underlying input: `rest` starts exactly where `matched`
+/// ends and is what the next parser will see. On partial input `rest` only covers the data
+/// buffered so far.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::split_at_regex;
+///
+/// fn header<'i>(s: &mut &'i str) -> ModalResult<(&'i str, &'i str)> {
+///     split_at_regex(r"^(?s).*?\n\n").parse_next(s)
+/// }
+///
+/// assert_eq!(
+///     header.parse_peek("a: 1\n\nbody"),
+///     Ok(("body", ("a: 1\n\n", "body")))
+/// );
+/// ```
+#[inline(always)]
+pub fn split_at_regex<'h, Input, Re, Error>(re: Re) -> SplitAtParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    let re = re.into_regex();
+
+    SplitAtParser {
+        re,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+pub struct ClassifyParser<'h, I, R, F, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    map: F,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, F, D, E> Parser<I, D, E> for ClassifyParser<'h, I, R, F, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    F: FnMut(<I as Stream>::Slice) -> D,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<D, E> {
+        if <I as StreamIsPartial>::is_partial_supported() {
+            captures_impl::<_, _, _, true>(input, &self.re)
+        } else {
+            captures_impl::<_, _, _, false>(input, &self.re)
+        }
+        .map(|(slice, _)| (self.map)(slice))
+    }
+}
+
+/// Creates a parser that matches a regex and classifies the match with `map`.
+///
+/// The match is consumed like [`regex()`], and `map` turns the matched slice into a small
+/// discriminant such as an enum. This makes regex lexing the scrutinee of winnow's
+/// [`dispatch!`](winnow::combinator::dispatch), which then selects the branch to run.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::combinator::{dispatch, fail, preceded};
+/// use winnow::prelude::*;
+/// use winnow_regex::{classify, regex};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq)]
+/// enum Method {
+///     Get,
+///     Post,
+///     Other,
+/// }
+///
+/// fn request<'i>(s: &mut &'i str) -> ModalResult<(Method, &'i str)> {
+///     fn method(m: &str) -> Method {
+///         match m {
+///             "GET" => Method::Get,
+///             "POST" => Method::Post,
+///             _ => Method::Other,
+///         }
+///     }
+///
+///     dispatch! {classify(r"^[A-Z]+", method);
+///         Method::Get => preceded(" ", regex(r"^\S+")).map(|p| (Method::Get, p)),
+///         Method::Post => preceded(" ", regex(r"^/api/\S+")).map(|p| (Method::Post, p)),
+///         Method::Other => fail,
+///     }
+///     .parse_next(s)
+/// }
+///
+/// assert_eq!(request.parse_peek("GET /index"), Ok(("", (Method::Get, "/index"))));
+/// assert_eq!(request.parse_peek("POST /api/x"), Ok(("", (Method::Post, "/api/x"))));
+/// assert!(request.parse_peek("POST /index").is_err());
+/// assert!(request.parse_peek("PUT /index").is_err());
+/// ```
+#[inline(always)]
+pub fn classify<'h, Input, Re, F, D, Error>(
+    re: Re,
+    map: F,
+) -> ClassifyParser<'h, Input, Re::Output, F, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    F: FnMut(<Input as Stream>::Slice) -> D,
+    Error: ParserError<Input> + 'static,
+{
+    let re = re.into_regex();
+
+    ClassifyParser {
+        re,
+        map,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+pub struct MapGroupParser<'h, I, R, F, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    locs: R::CaptureLocations,
+    group: usize,
+    map: F,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, F, T, E2, E> Parser<I, T, E> for MapGroupParser<'h, I, R, F, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    <I as Stream>::Slice: AsRef<<R::CaptureLocations as CaptureLocations>::Input>,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    <R::CaptureLocations as CaptureLocations>::Input:
+        Index<Range<usize>, Output = <R::CaptureLocations as CaptureLocations>::Input>,
+    F: FnMut(&<R::CaptureLocations as CaptureLocations>::Input) -> Result<T, E2>,
+    E: ParserError<I> + FromExternalError<I, E2>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<T, E> {
+        let start = input.checkpoint();
+        let slice = if <I as StreamIsPartial>::is_partial_supported() {
+            captures_into::<_, _, _, true>(input, &self.re, &mut self.locs)
+        } else {
+            captures_into::<_, _, _, false>(input, &self.re, &mut self.locs)
+        }?;
+
+        let Some((group_start, group_end)) = self.locs.get(self.group) else {
+            input.reset(&start);
+            return Err(ParserError::from_input(input));
+        };
+        (self.map)(&slice.as_ref()[group_start..group_end]).map_err(|e| {
+            input.reset(&start);
+            E::from_external_error(input, e)
+        })
+    }
+}
+
+/// Creates a parser that matches a regex and converts group `group` with a fallible `map`.
+///
+/// The whole match is consumed like [`regex()`], and only the text of the group is passed to
+/// `map`. An `Err` returned by `map` is reported through [`FromExternalError`], so the error
+/// carries the cause instead of forcing an `unwrap` in a `.map(..)`. If the group does not
+/// exist or did not participate in the match, the parser fails like a non-matching regex. On
+/// any error the input is left untouched.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::map_group;
+///
+/// fn color(s: &mut &str) -> ModalResult<u32> {
+///     map_group(r"^#([[:xdigit:]]+)", 1, |hex: &str| u32::from_str_radix(hex, 16)).parse_next(s)
+/// }
+///
+/// assert_eq!(color.parse_peek("#ff8000;"), Ok((";", 0xff8000)));
+/// // The group matches, but the value overflows `u32`.
+/// assert!(color.parse_peek("#123456789").is_err());
+/// ```
+#[inline(always)]
+pub fn map_group<'h, Input, Re, F, T, E2, Error>(
+    re: Re,
+    group: usize,
+    map: F,
+) -> MapGroupParser<'h, Input, Re::Output, F, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    F: FnMut(
+        &<<Re::Output as Regex>::CaptureLocations as CaptureLocations>::Input,
+    ) -> Result<T, E2>,
+    Error: ParserError<Input> + FromExternalError<Input, E2> + 'static,
+{
+    let re = re.into_regex();
+
+    MapGroupParser {
+        locs: re.capture_locations(),
+        re,
+        group,
+        map,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+pub struct CapturesEqParser<'h, I, R, T, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    inner: CapturesParser<'h, I, R, E>,
+    group: usize,
+    expected: T,
+}
+
+impl<'h, I, R, T, E> Parser<I, Captures<<I as Stream>::Slice, R::CaptureLocations>, E>
+    for CapturesEqParser<'h, I, R, T, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    <I as Stream>::Slice: AsRef<<R::CaptureLocations as CaptureLocations>::Input>,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    <R::CaptureLocations as CaptureLocations>::Input:
+        PartialEq + Index<Range<usize>, Output = <R::CaptureLocations as CaptureLocations>::Input>,
+    T: AsRef<<R::CaptureLocations as CaptureLocations>::Input>,
+    E: ParserError<I>,
+{
+    fn parse_next(
+        &mut self,
+        input: &mut I,
+    ) -> Result<Captures<<I as Stream>::Slice, R::CaptureLocations>, E> {
+        let start = input.checkpoint();
+        let caps = self.inner.parse_next(input)?;
+        if caps.get(self.group) == Some(self.expected.as_ref()) {
+            Ok(caps)
+        } else {
+            input.reset(&start);
+            Err(ParserError::from_input(input))
+        }
+    }
+}
+
+/// Like [`captures`], but only succeeds if group `group` equals `expected`.
+///
+/// This compares a capture against a value known only at runtime, such as the name of an
+/// opening tag when parsing its closing tag, which a regex on its own cannot express. A group
+/// that differs from `expected`, does not exist or did not participate fails the parse like a
+/// non-matching regex, leaving the input untouched.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::{captures, captures_eq, regex};
+///
+/// fn element<'i>(s: &mut &'i str) -> ModalResult<&'i str> {
+///     let open = captures(r"^<(\w+)>").parse_next(s)?;
+///     let body = regex(r"^[^<]*").parse_next(s)?;
+///     captures_eq(r"^</(\w+)>", 1, &open[1]).parse_next(s)?;
+///     Ok(body)
+/// }
+///
+/// assert_eq!(element.parse_peek("<b>bold</b>"), Ok(("", "bold")));
+/// assert!(element.parse_peek("<b>bold</i>").is_err());
+/// ```
+#[inline(always)]
+pub fn captures_eq<'h, Input, Re, T, Error>(
+    re: Re,
+    group: usize,
+    expected: T,
+) -> CapturesEqParser<'h, Input, Re::Output, T, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    CapturesEqParser {
+        inner: captures(re),
+        group,
+        expected,
+    }
+}
+
+pub struct RepeatedGroupParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    locs: R::CaptureLocations,
+    group: usize,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> Parser<I, Vec<<I as Stream>::Slice>, E> for RepeatedGroupParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<Vec<<I as Stream>::Slice>, E> {
+        let mut groups = Vec::new();
+        loop {
+            let found = find_into(input, &self.re, &mut self.locs);
+            match found {
+                Some((0, _)) => break,
+                None if !input.is_partial() => break,
+                _ => {}
+            }
+            let start = input.checkpoint();
+            if <I as StreamIsPartial>::is_partial_supported() {
+                consume_match::<_, _, E, true>(input, found)
+            } else {
+                consume_match::<_, _, E, false>(input, found)
+            }?;
+            if let Some(span) = self.locs.get(self.group) {
+                groups.push(slice_at(input, &start, span));
+            }
+        }
+        Ok(groups)
+    }
+}
+
+/// Creates a parser that applies `re` repeatedly and collects group `group` of every match.
+///
+/// A capturing group inside a repetition, like `(\w+)` in `^(?:(\w+),)+`, only reports its
+/// **last** iteration; this is how the `regex` crate (and most engines) work, and a single
+/// regex cannot do better. `repeated_group` instead matches the repeated part on its own, as in
+/// `^(\w+),`, over and over from the current position, and keeps the requested group of each
+/// iteration. Group `0` collects the whole matches.
+///
+/// Repetition stops at the first position where `re` does not match or only matches the empty
+/// string, so the loop always makes progress. Iterations where the
+/// group did not participate are skipped. On partial input the same rules as [`regex()`] apply to
+/// every iteration, so reaching the end of the buffer returns `Incomplete`.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::{Captures, captures, repeated_group};
+///
+/// fn words<'i>(s: &mut &'i str) -> ModalResult<Vec<&'i str>> {
+///     repeated_group(r"^(\w+),", 1).parse_next(s)
+/// }
+/// assert_eq!(words.parse_peek("a,b,c,;"), Ok((";", vec!["a", "b", "c"])));
+///
+/// // A single regex only keeps the last iteration of the group.
+/// fn last<'i>(s: &mut &'i str) -> ModalResult<String> {
+///     captures(r"^(?:(\w+),)+")
+///         .map(|c: Captures<&str, _>| c[1].to_owned())
+///         .parse_next(s)
+/// }
+/// assert_eq!(last.parse_peek("a,b,c,;"), Ok((";", "c".to_owned())));
+/// ```
+#[inline(always)]
+pub fn repeated_group<'h, Input, Re, Error>(
+    re: Re,
+    group: usize,
+) -> RepeatedGroupParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    let re = re.into_regex();
+
+    RepeatedGroupParser {
+        locs: re.capture_locations(),
+        re,
+        group,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+pub struct CountMatchesParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> Parser<I, usize, E> for CountMatchesParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<usize, E> {
+        let mut count = 0;
+        loop {
+            let found = match self.re.find(input.peek_finish()) {
+                Some((0, end)) => Some((end, ())),
+                _ => None,
+            };
+            match found {
+                Some((0, _)) => break,
+                None if !input.is_partial() => break,
+                _ => {}
+            }
+            if <I as StreamIsPartial>::is_partial_supported() {
+                consume_match::<_, _, E, true>(input, found)
+            } else {
+                consume_match::<_, _, E, false>(input, found)
+            }?;
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+/// Creates a parser that applies `re` repeatedly and returns how many times it matched.
+///
+/// This is [`repeated_group`] for when only the number of matches matters: every match is
+/// consumed but nothing is collected, so no `Vec` is allocated. Like there, repetition stops at
+/// the first position where `re` does not match or only matches the empty string, and on
+/// partial input reaching the end of the buffer returns `Incomplete`. No match at all gives a
+/// count of zero rather than an error.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::count_matches;
+///
+/// fn numbers(s: &mut &str) -> ModalResult<usize> {
+///     count_matches(r"^\d+,").parse_next(s)
+/// }
+///
+/// assert_eq!(numbers.parse_peek("1,22,333,;"), Ok((";", 3)));
+/// assert_eq!(numbers.parse_peek(";"), Ok((";", 0)));
+/// ```
+#[inline(always)]
+pub fn count_matches<'h, Input, Re, Error>(
+    re: Re,
+) -> CountMatchesParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    let re = re.into_regex();
+
+    CountMatchesParser {
+        re,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+/// Every group of a match as produced by [`captures_detailed`]: its range within the match and
+/// its text, or `None` if it did not participate.
+pub type DetailedGroups<Slice> = Vec<Option<(Range<usize>, Slice)>>;
+
+pub struct CapturesDetailedParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> Parser<I, DetailedGroups<<I as Stream>::Slice>, E>
+    for CapturesDetailedParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<DetailedGroups<<I as Stream>::Slice>, E> {
+        let start = input.checkpoint();
+        let (_, locs) = if <I as StreamIsPartial>::is_partial_supported() {
+            captures_impl::<_, _, _, true>(input, &self.re)
+        } else {
+            captures_impl::<_, _, _, false>(input, &self.re)
+        }?;
+        Ok((0..locs.len())
+            .map(|i| {
+                let span = locs.get(i)?;
+                Some((span.0..span.1, slice_at(input, &start, span)))
+            })
+            .collect())
+    }
+}
+
+/// Creates a parser that returns the range and text of every group of the match.
+///
+/// The result has one entry per group, starting with group 0 for the whole match. Ranges are
+/// relative to the start of the match, and groups that did not participate are `None`. This is
+/// the bulk form of [`Captures::range`] and [`Captures::get`], convenient for consumers such as
+/// syntax highlighters that visit every group anyway. Unlike [`Captures`], the texts are slices
+/// of the input, so they outlive the parser output.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::{DetailedGroups, captures_detailed};
+///
+/// fn assignment<'i>(s: &mut &'i str) -> ModalResult<DetailedGroups<&'i str>> {
+///     captures_detailed(r"^(\w+)\s*=\s*(\d+)(;)?").parse_next(s)
+/// }
+///
+/// assert_eq!(
+///     assignment.parse_peek("x = 42\n"),
+///     Ok(("\n", vec![Some((0..6, "x = 42")), Some((0..1, "x")), Some((4..6, "42")), None]))
+/// );
+/// ```
+#[inline(always)]
+pub fn captures_detailed<'h, Input, Re, Error>(
+    re: Re,
+) -> CapturesDetailedParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    let re = re.into_regex();
+
+    CapturesDetailedParser {
+        re,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+pub struct CapturesCowParser<'h, I, R, F, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    transform: F,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, 'i, I, R, F, H, E> Parser<I, Vec<Option<Cow<'i, H>>>, E>
+    for CapturesCowParser<'h, I, R, F, E>
+where
+    I: Stream<Slice = &'i H> + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    F: FnMut(&'i H) -> Cow<'i, H>,
+    H: ?Sized + ToOwned + 'i,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<Vec<Option<Cow<'i, H>>>, E> {
+        let start = input.checkpoint();
+        let (_, locs) = if <I as StreamIsPartial>::is_partial_supported() {
+            captures_impl::<_, _, _, true>(input, &self.re)
+        } else {
+            captures_impl::<_, _, _, false>(input, &self.re)
+        }?;
+        Ok((0..locs.len())
+            .map(|i| {
+                let span = locs.get(i)?;
+                Some((self.transform)(slice_at(input, &start, span)))
+            })
+            .collect())
+    }
+}
+
+/// Creates a parser that passes the text of every group through `transform`, returning a
+/// [`Cow`] per group.
+///
+/// The result has one entry per group, starting with group 0 for the whole match, and groups
+/// that did not participate are `None`. `transform` decides per group whether to borrow the
+/// input or allocate, so a parser that unescapes its groups only pays for an allocation when a
+/// group actually contains an escape. Pass `Cow::Borrowed` to keep every group as is.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use std::borrow::Cow;
+/// use winnow::prelude::*;
+/// use winnow_regex::captures_cow;
+///
+/// fn unescape(s: &str) -> Cow<'_, str> {
+///     if s.contains('\\') {
+///         Cow::Owned(s.replace("\\\"", "\""))
+///     } else {
+///         Cow::Borrowed(s)
+///     }
+/// }
+///
+/// fn string<'i>(s: &mut &'i str) -> ModalResult<Cow<'i, str>> {
+///     captures_cow(r#"^"((?:[^"\\]|\\.)*)""#, unescape)
+///         .map(|mut groups| groups.swap_remove(1).unwrap())
+///         .parse_next(s)
+/// }
+///
+/// assert_eq!(string.parse_peek(r#""plain" x"#), Ok((" x", Cow::Borrowed("plain"))));
+/// assert_eq!(string.parse_peek(r#""say \"hi\"""#), Ok(("", Cow::Owned(r#"say "hi""#.into()))));
+/// ```
+#[inline(always)]
+pub fn captures_cow<'h, Input, Re, F, Error>(
+    re: Re,
+    transform: F,
+) -> CapturesCowParser<'h, Input, Re::Output, F, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    CapturesCowParser {
+        re: re.into_regex(),
+        transform,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+pub struct IsRegexParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> Parser<I, (), E> for IsRegexParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<(), E> {
+        if let Some((0, _)) = self.re.find(input.peek_finish()) {
+            Ok(())
+        } else if input.is_partial() {
+            Err(E::incomplete(input, Needed::Unknown))
+        } else {
+            Err(ParserError::from_input(input))
+        }
+    }
+}
+
+/// Creates a parser that checks whether a regex matches, without consuming anything.
+///
+/// This is the leanest way to validate input with a regex: it only asks the engine for the
+/// bounds of the match and never computes capture locations or builds a slice. Like
+/// [`regex()`], only a match starting at the current position counts.
+///
+/// On partial input a missing match returns `Incomplete`, as more data may produce one.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::is_regex;
+///
+/// fn starts_with_digit(s: &mut &str) -> ModalResult<()> {
+///     is_regex(r"^\d").parse_next(s)
+/// }
+///
+/// assert_eq!(starts_with_digit.parse_peek("1abc"), Ok(("1abc", ())));
+/// assert!(starts_with_digit.parse_peek("abc1").is_err());
+/// ```
+#[inline(always)]
+pub fn is_regex<'h, Input, Re, Error>(re: Re) -> IsRegexParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    let re = re.into_regex();
+
+    IsRegexParser {
+        re,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+pub struct PresentParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> Parser<I, bool, E> for PresentParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<bool, E> {
+        Ok(matches!(self.re.find(input.peek_finish()), Some((0, _))))
+    }
+}
+
+/// Creates a parser that reports whether a regex matches, without consuming anything.
+///
+/// Unlike [`is_regex`] or `peek(regex(..))`, this never fails: it returns `true` or `false`
+/// so a grammar can branch on the result. Like [`is_regex`], only a match starting at the
+/// current position counts.
+///
+/// The answer always reflects the data available now. On partial input this means `false`
+/// can turn into `true` once more data arrives, so use [`is_regex`] if that distinction
+/// matters.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::{regex, regex_present};
+///
+/// fn signed<'i>(s: &mut &'i str) -> ModalResult<(bool, &'i str)> {
+///     let negative = regex_present(r"^-").parse_next(s)?;
+///     let digits = regex(r"^-?\d+").parse_next(s)?;
+///     Ok((negative, digits))
+/// }
+///
+/// assert_eq!(signed.parse_peek("-12"), Ok(("", (true, "-12"))));
+/// assert_eq!(signed.parse_peek("12"), Ok(("", (false, "12"))));
+/// ```
+#[inline(always)]
+pub fn regex_present<'h, Input, Re, Error>(re: Re) -> PresentParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    let re = re.into_regex();
+
+    PresentParser {
+        re,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+/// Creates a parser that matches one of a fixed set of literal keywords.
+///
+/// Each keyword is escaped with [`regex::escape`], so it is matched literally, and the
+/// alternatives are tried longest first. This gives leftmost-longest keyword matching: with
+/// both `el` and `else` in the set, `else` wins on `"else"` no matter the declaration order.
+/// Keywords of the same length keep their relative order.
+///
+/// No word boundary is implied, so `else` also matches the start of `elsewhere`; append a
+/// check such as `regex(r"^\b")` if that matters. An empty set never matches.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::keywords;
+///
+/// fn keyword<'i>(s: &mut &'i str) -> ModalResult<&'i str> {
+///     keywords(&["el", "if", "else", "a+b"]).parse_next(s)
+/// }
+///
+/// assert_eq!(keyword.parse_peek("else {"), Ok((" {", "else")));
+/// assert_eq!(keyword.parse_peek("a+b"), Ok(("", "a+b")));
+/// assert!(keyword.parse_peek("aab").is_err());
+/// ```
+#[inline(always)]
+pub fn keywords<'h, Input, Error>(keywords: &[&str]) -> RegexParser<'h, Input, regex::Regex, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    regex::Regex: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Error: ParserError<Input> + 'static,
+{
+    regex(keywords_pattern(keywords))
+}
+
+/// Builds an anchored alternation of the escaped `keywords`, longest first.
+fn keywords_pattern(keywords: &[&str]) -> String {
+    if keywords.is_empty() {
+        return r"[^\s\S]".to_owned();
+    }
+    let mut keywords = keywords.to_vec();
+    keywords.sort_by_key(|kw| core::cmp::Reverse(kw.len()));
+    let alternation: Vec<_> = keywords.into_iter().map(regex::escape).collect();
+    format!("^(?:{})", alternation.join("|"))
+}
+
+pub struct LongestMatchParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    res: Vec<R>,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> Parser<I, (usize, <I as Stream>::Slice), E> for LongestMatchParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<(usize, <I as Stream>::Slice), E> {
+        first_match(input, &self.res, true)
+    }
+}
+
+/// Creates a parser that tries every pattern at the current position and consumes the longest
+/// match, returning the index of the winning pattern along with the matched slice.
+///
+/// This is the maximal munch rule of a lexer: with both `=` and `==` among the patterns, `==`
+/// wins on `"=="` regardless of the order they were given in. Matches of equal length go to the
+/// pattern that comes first. Patterns that are tried in order and stop at the first success are
+/// better served by winnow's `alt`.
+///
+/// Each pattern runs over the remaining input, so anchor them with `^` like any other pattern
+/// in this crate. On partial input, a winning match that reaches the end of the buffer returns
+/// `Incomplete`, since a longer match may follow.
+///
+/// # Panics
+///
+/// Panics if any of the regex patterns fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::longest_match;
+///
+/// fn token<'i>(s: &mut &'i str) -> ModalResult<(usize, &'i str)> {
+///     longest_match([r"^=", r"^==", r"^[a-z]+", r"^if"]).parse_next(s)
+/// }
+///
+/// assert_eq!(token.parse_peek("== x"), Ok((" x", (1, "=="))));
+/// assert_eq!(token.parse_peek("= x"), Ok((" x", (0, "="))));
+/// // `[a-z]+` and `if` tie, and the earlier pattern wins.
+/// assert_eq!(token.parse_peek("if x"), Ok((" x", (2, "if"))));
+/// ```
+#[inline(always)]
+pub fn longest_match<'h, Input, Re, Error>(
+    patterns: impl IntoIterator<Item = Re>,
+) -> LongestMatchParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    LongestMatchParser {
+        res: patterns.into_iter().map(RegexPattern::into_regex).collect(),
+        _marker: core::marker::PhantomData,
+    }
+}
+
+pub struct AnyOfParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    res: Vec<R>,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> Parser<I, (usize, <I as Stream>::Slice), E> for AnyOfParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<(usize, <I as Stream>::Slice), E> {
+        first_match(input, &self.res, false)
+    }
+}
+
+/// Creates a parser that tries precompiled regexes in order and consumes the first match,
+/// returning the index of the regex that matched along with the matched slice.
+///
+/// The regexes can be built at runtime, e.g. from configuration, and are used as they are, so
+/// there is no recompilation or joining of patterns as with [`keywords`]. Anchor them with `^`
+/// like any other pattern in this crate. Use [`longest_match`] instead when the longest match
+/// should win regardless of order, and [`bytes::any_of`] for byte regexes.
+///
+/// On partial input the rules of [`regex()`] apply to the first regex that matches.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::any_of;
+///
+/// let res = [r"^\d+", r"^[a-z]+", r"^\S"].map(|p| regex::Regex::new(p).unwrap());
+/// let mut token = any_of::<_, winnow::error::ContextError>(res.to_vec());
+///
+/// assert_eq!(token.parse_peek("42 x"), Ok((" x", (0, "42"))));
+/// assert_eq!(token.parse_peek("ab1"), Ok(("1", (1, "ab"))));
+/// assert_eq!(token.parse_peek("+"), Ok(("", (2, "+"))));
+/// ```
+#[inline(always)]
+pub fn any_of<'h, Input, Error>(
+    regexes: Vec<regex::Regex>,
+) -> AnyOfParser<'h, Input, regex::Regex, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    regex::Regex: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Error: ParserError<Input> + 'static,
+{
+    AnyOfParser {
+        res: regexes,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+pub struct WsParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    ws: R,
+    /// Whether the whitespace comes before the token rather than after it.
+    leading: bool,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> Parser<I, <I as Stream>::Slice, E> for WsParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<<I as Stream>::Slice, E> {
+        let start = input.checkpoint();
+        let res = if <I as StreamIsPartial>::is_partial_supported() {
+            self.parse_impl::<true>(input)
+        } else {
+            self.parse_impl::<false>(input)
+        };
+        res.inspect_err(|_| input.reset(&start))
+    }
+}
+
+impl<'h, I, R, E> WsParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_impl<const PARTIAL: bool>(&self, input: &mut I) -> Result<<I as Stream>::Slice, E> {
+        if self.leading {
+            captures_impl::<_, _, E, PARTIAL>(input, &self.ws)?;
+        }
+        let (token, _) = captures_impl::<_, _, E, PARTIAL>(input, &self.re)?;
+        if !self.leading {
+            captures_impl::<_, _, E, PARTIAL>(input, &self.ws)?;
+        }
+        Ok(token)
+    }
+}
+
+/// Like [`regex()`], but also consumes the whitespace that follows the match.
+///
+/// After the token matches, any trailing whitespace (as matched by `\s*`) is skipped, and only
+/// the token is returned. This is the usual shape of a lexer token that ignores the spacing
+/// after it.
+///
+/// On partial input, whitespace running up to the end of the buffer returns `Incomplete`, since
+/// more of it may follow. This includes a token with no trailing whitespace that ends exactly
+/// at the end of the buffer. On any error the input is left untouched.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::regex_ws;
+///
+/// fn token<'i>(s: &mut &'i str) -> ModalResult<&'i str> {
+///     regex_ws(r"^\w+").parse_next(s)
+/// }
+///
+/// assert_eq!(token.parse_peek("let  \n x"), Ok(("x", "let")));
+/// assert_eq!(token.parse_peek("let"), Ok(("", "let")));
+/// ```
+#[inline(always)]
+pub fn regex_ws<'h, Input, Re, Error>(re: Re) -> WsParser<'h, Input, regex::Regex, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern<Output = regex::Regex>,
+    regex::Regex: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    WsParser {
+        re: re.into_regex(),
+        ws: r"^\s*".into_regex(),
+        leading: false,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+/// Like [`regex()`], but first skips any whitespace in front of the match.
+///
+/// Leading whitespace (as matched by `\s*`) is consumed, then the token is matched right after
+/// it, and only the token is returned. This is the counterpart of [`regex_ws`] for grammars that
+/// treat the spacing before a token as insignificant.
+///
+/// On partial input, whitespace running up to the end of the buffer returns `Incomplete`, since
+/// more of it, or the token, may follow. On any error the input is left untouched.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::ws_regex;
+///
+/// fn token<'i>(s: &mut &'i str) -> ModalResult<&'i str> {
+///     ws_regex(r"^\w+").parse_next(s)
+/// }
+///
+/// assert_eq!(token.parse_peek(" \n let x"), Ok((" x", "let")));
+/// assert_eq!(token.parse_peek("let"), Ok(("", "let")));
+/// ```
+#[inline(always)]
+pub fn ws_regex<'h, Input, Re, Error>(re: Re) -> WsParser<'h, Input, regex::Regex, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern<Output = regex::Regex>,
+    regex::Regex: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    WsParser {
+        leading: true,
+        ..regex_ws(re)
+    }
+}
+
+pub struct SkipBomParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> Parser<I, <I as Stream>::Slice, E> for SkipBomParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    <I as Stream>::Slice: AsRef<[u8]>,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<<I as Stream>::Slice, E> {
+        const BOM: &[u8] = "\u{FEFF}".as_bytes();
+
+        let start = input.checkpoint();
+        let head = input.peek_finish();
+        let head = head.as_ref();
+        if head.starts_with(BOM) {
+            input.next_slice(BOM.len());
+        } else if !head.is_empty()
+            && BOM.starts_with(head)
+            && <I as StreamIsPartial>::is_partial_supported()
+            && input.is_partial()
+        {
+            // The rest of the BOM may still arrive.
+            return Err(E::incomplete(input, Needed::new(BOM.len() - head.len())));
+        }
+
+        let res = if <I as StreamIsPartial>::is_partial_supported() {
+            captures_impl::<_, _, E, true>(input, &self.re)
+        } else {
+            captures_impl::<_, _, E, false>(input, &self.re)
+        };
+        res.map(|(slice, _)| slice)
+            .inspect_err(|_| input.reset(&start))
+    }
+}
+
+/// Like [`regex()`], but first skips a UTF-8 byte order mark (`U+FEFF`) in front of the match.
+///
+/// Files saved by some editors start with a BOM, which keeps a `^`-anchored pattern from
+/// matching the first token. Without a BOM the regex is applied as usual. The BOM is consumed
+/// along with the match, and only the match is returned.
+///
+/// On partial input that ends in the middle of a BOM, `Incomplete` is returned until the rest
+/// of it arrives. On any error the input is left untouched.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::regex_skip_bom;
+///
+/// fn header<'i>(s: &mut &'i str) -> ModalResult<&'i str> {
+///     regex_skip_bom(r"^\w+").parse_next(s)
+/// }
+///
+/// assert_eq!(header.parse_peek("\u{FEFF}id,name"), Ok((",name", "id")));
+/// assert_eq!(header.parse_peek("id,name"), Ok((",name", "id")));
+/// ```
+#[inline(always)]
+pub fn regex_skip_bom<'h, Input, Re, Error>(re: Re) -> SkipBomParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    let re = re.into_regex();
+
+    SkipBomParser {
+        re,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+pub struct PrecededByParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    prefix: R,
+    re: R,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> Parser<I, <I as Stream>::Slice, E> for PrecededByParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<<I as Stream>::Slice, E> {
+        let start = input.checkpoint();
+        let res = if <I as StreamIsPartial>::is_partial_supported() {
+            captures_impl::<_, _, E, true>(input, &self.prefix)
+                .and_then(|_| captures_impl::<_, _, _, true>(input, &self.re))
+        } else {
+            captures_impl::<_, _, E, false>(input, &self.prefix)
+                .and_then(|_| captures_impl::<_, _, _, false>(input, &self.re))
+        };
+        res.map(|(slice, _)| slice)
+            .inspect_err(|_| input.reset(&start))
+    }
+}
+
+/// Matches `prefix` and then `re`, returning only the text matched by `re`.
+///
+/// The `regex` crate has no lookbehind, so a condition on the text before a match has to be
+/// expressed by matching that text as well. This parser does so by composition: the prefix is
+/// **consumed**, not just checked, and only the main match is returned. Both patterns are
+/// matched at the cursor, so anchor them with `^`.
+///
+/// On partial input, either match reaching the end of the buffer returns `Incomplete`, so a
+/// prefix that ends exactly at the end of the buffer needs more data even if `re` could match
+/// empty. On any error the input is left untouched.
+///
+/// # Panics
+///
+/// Panics if either regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::preceded_by;
+///
+/// fn amount<'i>(s: &mut &'i str) -> ModalResult<&'i str> {
+///     preceded_by(r"^\$\s*", r"^\d+(?:\.\d{2})?").parse_next(s)
+/// }
+///
+/// assert_eq!(amount.parse_peek("$ 12.50 total"), Ok((" total", "12.50")));
+/// assert!(amount.parse_peek("12.50").is_err());
+/// ```
+#[inline(always)]
+pub fn preceded_by<'h, Input, Prefix, Re, Error>(
+    prefix: Prefix,
+    re: Re,
+) -> PrecededByParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Prefix: RegexPattern<Output = Re::Output>,
+    Prefix::Error: Debug,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    PrecededByParser {
+        prefix: prefix.into_regex(),
+        re: re.into_regex(),
+        _marker: core::marker::PhantomData,
+    }
+}
+
+/// The error attached by [`regex_diagnostic`] when its regex does not match.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("expected a match for regex `{pattern}`")]
+pub struct RegexMismatch {
+    pattern: Box<str>,
+}
+
+impl RegexMismatch {
+    /// Returns the pattern that failed to match.
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+}
+
+pub struct DiagnosticParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> Parser<I, <I as Stream>::Slice, E> for DiagnosticParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I> + FromExternalError<I, RegexMismatch>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<<I as Stream>::Slice, E> {
+        let res: Result<_, E> = if <I as StreamIsPartial>::is_partial_supported() {
+            captures_impl::<_, _, _, true>(input, &self.re)
+        } else {
+            captures_impl::<_, _, _, false>(input, &self.re)
+        };
+        match res {
+            Ok((slice, _)) => Ok(slice),
+            Err(e) if e.is_incomplete() => Err(e),
+            Err(_) => Err(E::from_external_error(
+                input,
+                RegexMismatch {
+                    pattern: self.re.as_str().into(),
+                },
+            )),
+        }
+    }
+}
+
+/// Like [`regex()`], but a failed match reports which pattern was expected.
+///
+/// The error is built with [`FromExternalError`] from a [`RegexMismatch`] holding the pattern,
+/// so with winnow's [`ContextError`] it shows up as the cause of the error. `Incomplete` on
+/// partial input is passed through unchanged.
+///
+/// The error still points at the cursor, where the match was attempted. The `regex` crate does
+/// not report how far a failed match got before giving up, so there is no position of the
+/// divergence to attach; anchoring smaller patterns in sequence is the way to narrow a failure
+/// down.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::regex_diagnostic;
+///
+/// fn version<'i>(s: &mut &'i str) -> ModalResult<&'i str> {
+///     regex_diagnostic(r"^\d+\.\d+").parse_next(s)
+/// }
+///
+/// let err = version.parse_peek("v1.2").unwrap_err().into_inner().unwrap();
+/// assert_eq!(
+///     err.cause().unwrap().to_string(),
+///     r"expected a match for regex `^\d+\.\d+`"
+/// );
+/// ```
+#[inline(always)]
+pub fn regex_diagnostic<'h, Input, Re, Error>(
+    re: Re,
+) -> DiagnosticParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + FromExternalError<Input, RegexMismatch> + 'static,
+{
+    let re = re.into_regex();
+
+    DiagnosticParser {
+        re,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+/// The error attached by [`required_group`] when its group did not participate in the match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("capture group {group} did not participate in the match")]
+pub struct MissingGroup {
+    group: usize,
+}
+
+impl MissingGroup {
+    /// Returns the index of the group that was missing.
+    pub fn group(&self) -> usize {
+        self.group
+    }
+}
+
+pub struct RequiredGroupParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    locs: R::CaptureLocations,
+    group: usize,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> Parser<I, <I as Stream>::Slice, E> for RequiredGroupParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I> + FromExternalError<I, MissingGroup>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<<I as Stream>::Slice, E> {
+        let start = input.checkpoint();
+        if <I as StreamIsPartial>::is_partial_supported() {
+            captures_into::<_, _, _, true>(input, &self.re, &mut self.locs)
+        } else {
+            captures_into::<_, _, _, false>(input, &self.re, &mut self.locs)
+        }?;
+        match self.locs.get(self.group) {
+            Some(span) => Ok(slice_at(input, &start, span)),
+            None => {
+                input.reset(&start);
+                Err(E::from_external_error(
+                    input,
+                    MissingGroup { group: self.group },
+                ))
+            }
+        }
+    }
+}
+
+/// Creates a parser that matches `re` and returns the text of group `group`, failing if the
+/// group did not participate.
+///
+/// A group inside an alternation or an optional part may be absent from an otherwise
+/// successful match, where indexing [`Captures`] would panic. Here the parse fails instead,
+/// leaving the input untouched, with a [`MissingGroup`] error built through
+/// [`FromExternalError`]. A group index past the end of the pattern is reported the same way.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::required_group;
+///
+/// fn count<'i>(s: &mut &'i str) -> ModalResult<&'i str> {
+///     required_group(r"^(?:(\d+)|x)", 1).parse_next(s)
+/// }
+///
+/// assert_eq!(count.parse_peek("42;"), Ok((";", "42")));
+/// let err = count.parse_peek("x;").unwrap_err().into_inner().unwrap();
+/// assert_eq!(
+///     err.cause().unwrap().to_string(),
+///     "capture group 1 did not participate in the match"
+/// );
+/// ```
+#[inline(always)]
+pub fn required_group<'h, Input, Re, Error>(
+    re: Re,
+    group: usize,
+) -> RequiredGroupParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + FromExternalError<Input, MissingGroup> + 'static,
+{
+    let re = re.into_regex();
+
+    RequiredGroupParser {
+        locs: re.capture_locations(),
+        re,
+        group,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+/// What [`regex_window`] reports on partial input when the window runs out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowExhausted {
+    /// Return `Incomplete`, treating the window like the end of the available data.
+    Incomplete,
+    /// Fail the parse, treating the window as a hard limit.
+    Fail,
+}
+
+pub struct WindowParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    max: usize,
+    exhausted: WindowExhausted,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> Parser<I, <I as Stream>::Slice, E> for WindowParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<<I as Stream>::Slice, E> {
+        if input.eof_offset() <= self.max {
+            return if <I as StreamIsPartial>::is_partial_supported() {
+                captures_impl::<_, _, _, true>(input, &self.re)
+            } else {
+                captures_impl::<_, _, _, false>(input, &self.re)
+            }
+            .map(|(slice, _)| slice);
+        }
+
+        // The window ends at the last token boundary that fits in `max` bytes.
+        let window = input
+            .iter_offsets()
+            .map(|(offset, _)| offset)
+            .take_while(|&offset| offset <= self.max)
+            .last()
+            .unwrap_or(0);
+        let mut locs = self.re.capture_locations();
+        match self.re.captures_read(&mut locs, input.peek_slice(window)) {
+            Some((0, end)) if end < window => Ok(input.next_slice(end)),
+            _ if input.is_partial() && self.exhausted == WindowExhausted::Incomplete => {
+                Err(E::incomplete(input, Needed::Unknown))
+            }
+            _ => Err(ParserError::from_input(input)),
+        }
+    }
+}
+
+/// Like [`regex()`], but only looks at the first `max_bytes` bytes of the remaining input.
+///
+/// This caps the work done on a large buffer: the regex runs over the window alone, which is
+/// cut at the last character boundary that fits. Anchors like `$` and `\b` therefore see the
+/// window's end as the end of the text.
+///
+/// When the remaining input fits in the window, this behaves exactly like [`regex()`]. Otherwise
+/// a match is only accepted if it ends strictly inside the window, since one reaching the edge
+/// might have continued. If there is no such match, the window is exhausted: complete input
+/// fails, and partial input does what `exhausted` says, either returning `Incomplete` as if the
+/// window were the end of the buffered data, or failing because the window is a hard limit.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::{WindowExhausted, regex_window};
+///
+/// fn number<'i>(s: &mut &'i str) -> ModalResult<&'i str> {
+///     regex_window(r"^\d+", 4, WindowExhausted::Fail).parse_next(s)
+/// }
+///
+/// assert_eq!(number.parse_peek("123 and much more"), Ok((" and much more", "123")));
+/// assert!(number.parse_peek("12345 and much more").is_err());
+/// ```
+#[inline(always)]
+pub fn regex_window<'h, Input, Re, Error>(
+    re: Re,
+    max_bytes: usize,
+    exhausted: WindowExhausted,
+) -> WindowParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    let re = re.into_regex();
+
+    WindowParser {
+        re,
+        max: max_bytes,
+        exhausted,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+pub struct CapturesMapParser<'h, I, R, E = ErrMode<ContextError>, M = HashMap<String, String>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    inner: CapturesParser<'h, I, R, E>,
+    _marker: core::marker::PhantomData<M>,
+}
+
+impl<'h, I, R, E, M> Parser<I, M, E> for CapturesMapParser<'h, I, R, E, M>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    <I as Stream>::Slice: AsRef<str>,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    R::CaptureLocations: CaptureLocations<Input = str>,
+    E: ParserError<I>,
+    M: FromIterator<(String, String)>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<M, E> {
+        let caps = self.inner.parse_next(input)?;
+        Ok(caps
+            .named_iter()
+            .filter_map(|(name, text)| Some((name.to_owned(), text?.to_owned())))
+            .collect())
+    }
+}
+
+/// Creates a parser that returns the named capture groups as an owned map.
+///
+/// Every named group that participated in the match becomes an entry from its name to its
+/// text; groups that did not participate are left out, as are unnamed groups. Since keys and
+/// values are owned, the map outlives the input. Iteration order of the [`HashMap`] is
+/// unspecified.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use winnow::prelude::*;
+/// use winnow_regex::captures_map;
+///
+/// fn record(s: &mut &str) -> ModalResult<HashMap<String, String>> {
+///     captures_map(r"^(?<key>\w+)=(?<value>\w+)(?:;(?<comment>.*))?").parse_next(s)
+/// }
+///
+/// let map = record.parse_peek("name=winnow").unwrap().1;
+/// assert_eq!(map["key"], "name");
+/// assert_eq!(map["value"], "winnow");
+/// assert!(!map.contains_key("comment"));
+/// ```
+#[inline(always)]
+pub fn captures_map<'h, Input, Re, Error>(re: Re) -> CapturesMapParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    CapturesMapParser {
+        inner: captures(re),
+        _marker: core::marker::PhantomData,
+    }
+}
+
+/// Like [`captures_map`], but returns a [`BTreeMap`] sorted by group name.
+///
+/// The deterministic order suits serialized output and golden tests. As with [`captures_map`],
+/// only named groups that participated in the match are included.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::BTreeMap;
+/// use winnow::prelude::*;
+/// use winnow_regex::captures_btreemap;
+///
+/// fn record(s: &mut &str) -> ModalResult<BTreeMap<String, String>> {
+///     captures_btreemap(r"^(?<z>\w)(?<a>\w)(?<m>\d)?").parse_next(s)
+/// }
+///
+/// let map = record.parse_peek("xy").unwrap().1;
+/// assert_eq!(format!("{map:?}"), r#"{"a": "y", "z": "x"}"#);
+/// ```
+#[inline(always)]
+pub fn captures_btreemap<'h, Input, Re, Error>(
+    re: Re,
+) -> CapturesMapParser<'h, Input, Re::Output, Error, BTreeMap<String, String>>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    CapturesMapParser {
+        inner: captures(re),
+        _marker: core::marker::PhantomData,
+    }
+}
+
+pub struct CapturesOwnedParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    inner: CapturesParser<'h, I, R, E>,
+}
+
+impl<'h, I, R, E> Parser<I, OwnedCaptures<<R::CaptureLocations as CaptureLocations>::Input>, E>
+    for CapturesOwnedParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    <I as Stream>::Slice: AsRef<<R::CaptureLocations as CaptureLocations>::Input>,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    <R::CaptureLocations as CaptureLocations>::Input:
+        ToOwned + Index<Range<usize>, Output = <R::CaptureLocations as CaptureLocations>::Input>,
+    E: ParserError<I>,
+{
+    fn parse_next(
+        &mut self,
+        input: &mut I,
+    ) -> Result<OwnedCaptures<<R::CaptureLocations as CaptureLocations>::Input>, E> {
+        let caps = self.inner.parse_next(input)?;
+        Ok(caps.to_owned_captures())
+    }
+}
+
+/// Like [`captures`], but returns [`OwnedCaptures`] that do not borrow the input.
+///
+/// The matched text is copied once and the groups keep their structure and names, so the
+/// result can be stored in an AST that outlives the input. Use [`captures_map`] instead if only
+/// the named groups are needed.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::{OwnedCaptures, captures_owned};
+///
+/// fn header(s: &mut &str) -> ModalResult<OwnedCaptures<str>> {
+///     captures_owned(r"^(?<name>[\w-]+):\s*(?<value>.*)").parse_next(s)
+/// }
+///
+/// let caps = {
+///     let line = String::from("Content-Type: text/plain");
+///     header.parse_peek(&line).unwrap().1
+/// };
+/// assert_eq!(&caps[1], "Content-Type");
+/// assert_eq!(caps.range(2), Some(14..24));
+/// ```
+#[inline(always)]
+pub fn captures_owned<'h, Input, Re, Error>(
+    re: Re,
+) -> CapturesOwnedParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    CapturesOwnedParser {
+        inner: captures(re),
+    }
+}
+
+pub struct PrefilteredParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    first_bytes: [bool; 256],
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> Parser<I, <I as Stream>::Slice, E> for PrefilteredParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    <I as Stream>::Slice: AsRef<[u8]>,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<<I as Stream>::Slice, E> {
+        match input.peek_finish().as_ref().first() {
+            Some(&b) if self.first_bytes[usize::from(b)] => {}
+            None if input.is_partial() => return Err(E::incomplete(input, Needed::Unknown)),
+            _ => return Err(ParserError::from_input(input)),
+        }
+        if <I as StreamIsPartial>::is_partial_supported() {
+            captures_impl::<_, _, _, true>(input, &self.re)
+        } else {
+            captures_impl::<_, _, _, false>(input, &self.re)
+        }
+        .map(|(slice, _)| slice)
+    }
+}
+
+/// Like [`regex()`], but fails fast unless the input starts with one of `first_bytes`.
+///
+/// The first byte of the remaining input is looked up in `first_bytes` before the regex runs,
+/// so a mismatch costs a table lookup instead of a full search. This pays off in large
+/// [`alt`](winnow::combinator::alt) chains of regex parsers, where most branches reject the
+/// input at its first character.
+///
+/// `first_bytes` must contain the first byte of every possible match; matches starting with
+/// another byte are rejected, and since an empty match has no first byte, empty input is
+/// always rejected too. The `regex` crate does not expose the literal prefixes it computes
+/// internally, which is why the set is provided by the caller.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::combinator::alt;
+/// use winnow::prelude::*;
+/// use winnow_regex::regex_prefiltered;
+///
+/// fn token<'i>(s: &mut &'i str) -> ModalResult<&'i str> {
+///     alt((
+///         regex_prefiltered(r"^[0-9]+", b"0123456789"),
+///         regex_prefiltered(r#"^"[^"]*""#, b"\""),
+///         regex_prefiltered(r"^[a-z]+", b"abcdefghijklmnopqrstuvwxyz"),
+///     ))
+///     .parse_next(s)
+/// }
+///
+/// assert_eq!(token.parse_peek("\"hi\" 1"), Ok((" 1", "\"hi\"")));
+/// assert_eq!(token.parse_peek("abc1"), Ok(("1", "abc")));
+/// ```
+#[inline(always)]
+pub fn regex_prefiltered<'h, Input, Re, Error>(
+    re: Re,
+    first_bytes: &[u8],
+) -> PrefilteredParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    let re = re.into_regex();
+    let mut table = [false; 256];
+    for &b in first_bytes {
+        table[usize::from(b)] = true;
+    }
+
+    PrefilteredParser {
+        re,
+        first_bytes: table,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+pub struct RichCapturesParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    inner: CapturesParser<'h, I, R, E>,
+}
+
+impl<'h, I, R, E> Parser<I, RichMatch<<I as Stream>::Slice, R::CaptureLocations>, E>
+    for RichCapturesParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Location + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(
+        &mut self,
+        input: &mut I,
+    ) -> Result<RichMatch<<I as Stream>::Slice, R::CaptureLocations>, E> {
+        let offset = input.current_token_start();
+        let caps = self.inner.parse_next(input)?;
+        Ok(RichMatch { caps, offset })
+    }
+}
+
+/// Creates a parser that returns a [`RichMatch`]: the captures plus their absolute position.
+///
+/// This suits diagnostic-heavy parsers that need the whole match, every group and where it all
+/// sits in the original input from a single parse. The input must track its position, e.g. by
+/// wrapping it in [`winnow::stream::LocatingSlice`].
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::combinator::preceded;
+/// use winnow::prelude::*;
+/// use winnow::stream::LocatingSlice;
+/// use winnow_regex::{RichMatch, rich_captures};
+///
+/// type Match<'i> = RichMatch<&'i str, regex::CaptureLocations>;
+///
+/// fn binding<'i>(s: &mut LocatingSlice<&'i str>) -> ModalResult<Match<'i>> {
+///     preceded("let ", rich_captures(r"^(?<name>\w+) = (\d+)")).parse_next(s)
+/// }
+///
+/// let m = binding.parse_next(&mut LocatingSlice::new("let x = 42;")).unwrap();
+/// assert_eq!(m.as_match(), "x = 42");
+/// assert_eq!(m.span(), 4..10);
+/// assert_eq!(m.range(2), Some(4..6));
+/// assert_eq!(m.absolute_range(2), Some(8..10));
+/// assert_eq!(&m[1], "x");
+/// ```
+#[inline(always)]
+pub fn rich_captures<'h, Input, Re, Error>(
+    re: Re,
+) -> RichCapturesParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Location + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    RichCapturesParser {
+        inner: captures(re),
+    }
+}
+
+pub struct LineColParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<LocatingSlice<I>>,
+{
+    re: R,
+    /// The offset, line and column of the previous match start.
+    last: (usize, usize, usize),
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> LineColParser<'h, I, R, E>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<LocatingSlice<I>>,
+{
+    /// Forgets the position of the previous match, so the next match is counted from the
+    /// start of its input.
+    ///
+    /// Call this before reusing the parser on another input, including the same buffer
+    /// refilled with different text.
+    pub fn reset(&mut self) {
+        self.last = (0, 1, 1);
+    }
+}
+
+impl<'h, I, R, E> Parser<LocatingSlice<I>, (<I as Stream>::Slice, usize, usize), E>
+    for LineColParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    <I as Stream>::Slice: AsRef<str>,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<LocatingSlice<I>>,
+{
+    fn parse_next(
+        &mut self,
+        input: &mut LocatingSlice<I>,
+    ) -> Result<(<I as Stream>::Slice, usize, usize), E> {
+        let offset = input.current_token_start();
+        let mut source = input.clone();
+        let (slice, _) = if <I as StreamIsPartial>::is_partial_supported() {
+            captures_impl::<_, _, _, true>(input, &self.re)
+        } else {
+            captures_impl::<_, _, _, false>(input, &self.re)
+        }?;
+
+        source.reset_to_start();
+        let full = source.peek_finish();
+        let full = full.as_ref();
+        // Count on from the previous match when moving forward, from the start otherwise.
+        if offset < self.last.0 || !full.is_char_boundary(self.last.0) {
+            self.reset();
+        }
+        let (from, mut line, mut col) = self.last;
+        for c in full[from..offset].chars() {
+            if c == '\n' {
+                line += 1;
+                col = 1;
+            } else {
+                col += 1;
+            }
+        }
+        self.last = (offset, line, col);
+        Ok((slice, line, col))
+    }
+}
+
+/// Like [`regex()`], but also returns the 1-based line and column where the match starts.
+///
+/// Lines are separated by `\n` and columns count characters, which is what a human-facing
+/// diagnostic wants to show. The position is worked out by counting newlines from the start
+/// of the input, so the input must be a [`LocatingSlice`] that still has it.
+///
+/// Counting is linear in the distance covered. The parser remembers the position of its last
+/// match and counts on from there when the next match lies further ahead, so a parser applied
+/// repeatedly while scanning forward, e.g. in a `repeat`, does linear work overall. Moving
+/// backwards starts over from the beginning. The parser cannot tell one input from another,
+/// so call [`LineColParser::reset`] before reusing it on a new input.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::combinator::{preceded, repeat};
+/// use winnow::prelude::*;
+/// use winnow::stream::LocatingSlice;
+/// use winnow_regex::{regex, regex_line_col};
+///
+/// fn words<'i>(s: &mut LocatingSlice<&'i str>) -> ModalResult<Vec<(&'i str, usize, usize)>> {
+///     repeat(1.., preceded(regex(r"^\s*"), regex_line_col(r"^\w+"))).parse_next(s)
+/// }
+///
+/// assert_eq!(
+///     words.parse(LocatingSlice::new("let x\n  y")),
+///     Ok(vec![("let", 1, 1), ("x", 1, 5), ("y", 2, 3)])
+/// );
+/// ```
+#[inline(always)]
+pub fn regex_line_col<'h, Input, Re, Error>(re: Re) -> LineColParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<LocatingSlice<Input>> + 'static,
+{
+    LineColParser {
+        re: re.into_regex(),
+        last: (0, 1, 1),
+        _marker: core::marker::PhantomData,
+    }
+}
+
+pub struct WithContextParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<LocatingSlice<I>>,
+{
+    re: R,
+    before: usize,
+    after: usize,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, 'i, I, R, E> Parser<LocatingSlice<I>, (&'i str, &'i str, &'i str), E>
+    for WithContextParser<'h, I, R, E>
+where
+    I: Stream<Slice = &'i str> + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<LocatingSlice<I>>,
+{
+    fn parse_next(
+        &mut self,
+        input: &mut LocatingSlice<I>,
+    ) -> Result<(&'i str, &'i str, &'i str), E> {
+        let offset = input.current_token_start();
+        let mut source = input.clone();
+        let (slice, _) = if <I as StreamIsPartial>::is_partial_supported() {
+            captures_impl::<_, _, _, true>(input, &self.re)
+        } else {
+            captures_impl::<_, _, _, false>(input, &self.re)
+        }?;
+
+        source.reset_to_start();
+        let text = source.peek_finish();
+        let end = offset + slice.len();
+        // Shrink the context to the nearest character boundaries.
+        let mut start = offset.saturating_sub(self.before);
+        while !text.is_char_boundary(start) {
+            start += 1;
+        }
+        let mut stop = end.saturating_add(self.after).min(text.len());
+        while !text.is_char_boundary(stop) {
+            stop -= 1;
+        }
+        Ok((slice, &text[start..offset], &text[end..stop]))
+    }
+}
+
+/// Like [`regex()`], but also returns up to `before` bytes preceding the match and up to `after`
+/// bytes following it, as snippets for error messages.
+///
+/// The preceding text has already been consumed, so the input must be a [`LocatingSlice`],
+/// which keeps the start of the input around. The snippets are clamped to the start of the
+/// input and the end of the available data, and shrunk to whole characters, so they can be
+/// shorter than asked for. The following text is only looked at, never consumed.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow::stream::LocatingSlice;
+/// use winnow_regex::regex_with_context;
+///
+/// fn value<'i>(s: &mut LocatingSlice<&'i str>) -> ModalResult<(&'i str, &'i str, &'i str)> {
+///     ("key = ", regex_with_context(r"^\d+", 4, 3)).map(|(_, v)| v).parse_next(s)
+/// }
+///
+/// assert_eq!(
+///     value.parse_next(&mut LocatingSlice::new("key = 42; rest")),
+///     Ok(("42", "y = ", "; r"))
+/// );
+/// ```
+#[inline(always)]
+pub fn regex_with_context<'h, Input, Re, Error>(
+    re: Re,
+    before: usize,
+    after: usize,
+) -> WithContextParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<LocatingSlice<Input>> + 'static,
+{
+    WithContextParser {
+        re: re.into_regex(),
+        before,
+        after,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+pub struct VoidParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> Parser<I, (), E> for VoidParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<(), E> {
+        let found = match self.re.find(input.peek_finish()) {
+            Some((0, end)) => Some((end, ())),
+            _ => None,
+        };
+        if <I as StreamIsPartial>::is_partial_supported() {
+            consume_match::<_, _, _, true>(input, found)
+        } else {
+            consume_match::<_, _, _, false>(input, found)
+        }
+        .map(|_| ())
+    }
+}
+
+/// Like [`regex()`], but discards the match.
+///
+/// This is for matches that only advance the input, such as a fixed prefix or a separator.
+/// Compared to `regex(..).void()`, the match is found without computing capture locations,
+/// which avoids an allocation per call. Partial input is handled exactly like [`regex()`].
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::combinator::preceded;
+/// use winnow::prelude::*;
+/// use winnow_regex::{regex, regex_void};
+///
+/// fn value<'i>(s: &mut &'i str) -> ModalResult<&'i str> {
+///     preceded(regex_void(r"^\s*=\s*"), regex(r"^\w+")).parse_next(s)
+/// }
+///
+/// assert_eq!(value.parse_peek("  = abc"), Ok(("", "abc")));
+/// ```
+#[inline(always)]
+pub fn regex_void<'h, Input, Re, Error>(re: Re) -> VoidParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    let re = re.into_regex();
+
+    VoidParser {
+        re,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+/// An iterator over the matches of a regex in a complete input.
+///
+/// Created by [`find_iter`].
+pub struct FindIter<'h, R, H>
+where
+    R: Regex,
+    H: ?Sized,
+{
+    re: R,
+    names: CaptureNames,
+    haystack: &'h H,
+    pos: usize,
+    last_end: Option<usize>,
+}
+
+impl<'h, R, H> Iterator for FindIter<'h, R, H>
+where
+    R: Regex<Haystack<'h> = &'h H>,
+    R::CaptureLocations: CaptureLocations<Input = H>,
+    H: ?Sized + AsRef<[u8]> + Index<Range<usize>, Output = H>,
+{
+    type Item = RichMatch<&'h H, R::CaptureLocations>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut locs = self.re.capture_locations();
+        loop {
+            if self.pos > self.haystack.as_ref().len() {
+                return None;
+            }
+            let (start, end) = self
+                .re
+                .captures_read_at(&mut locs, self.haystack, self.pos)?;
+            if start == end {
+                // Step past an empty match so the search makes progress, and skip one that
+                // directly follows the previous match.
+                self.pos = end + 1;
+                if self.last_end == Some(end) {
+                    continue;
+                }
+            } else {
+                self.pos = end;
+            }
+            self.last_end = Some(end);
+
+            let caps = Captures {
+                slice: &self.haystack[start..end],
+                locs,
+                names: self.names.clone(),
+                base: start,
+            };
+            return Some(RichMatch {
+                caps,
+                offset: start,
+            });
+        }
+    }
+}
+
+/// Returns an iterator over the successive non-overlapping matches of a regex in `haystack`.
+///
+/// This is a standalone helper, not a parser: it mirrors [`regex::Regex::captures_iter`] for
+/// use outside of winnow, but yields this crate's [`RichMatch`], so the same accessors work on
+/// both sides. Unlike the parsers, matches are found anywhere in the input, and each
+/// [`RichMatch::offset`] is the absolute position of the match in `haystack`. Empty matches
+/// follow the rules of the `regex` crate.
+///
+/// Pass a [`regex::bytes::Regex`] to search a `&[u8]`.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow_regex::find_iter;
+///
+/// let dates: Vec<_> = find_iter(r"(?<y>\d{4})-(?<m>\d{2})", "from 2024-05 to 2025-01")
+///     .map(|m| (m.offset(), m[1].to_owned(), m[2].to_owned()))
+///     .collect();
+/// assert_eq!(dates, [(5, "2024".into(), "05".into()), (16, "2025".into(), "01".into())]);
+/// ```
+pub fn find_iter<'h, Re, H>(re: Re, haystack: &'h H) -> FindIter<'h, Re::Output, H>
+where
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = &'h H>,
+    Re::Error: Debug,
+    H: ?Sized,
+{
+    let re = re.into_regex();
+
+    FindIter {
+        names: capture_names(&re),
+        re,
+        haystack,
+        pos: 0,
+        last_end: None,
+    }
+}
+
+/// An iterator over the matches of a regex in the current buffer of a stream.
+///
+/// Created by [`scan`].
+pub struct Scanner<'h, I, R>
+where
+    R: Regex,
+{
+    re: R,
+    locs: R::CaptureLocations,
+    input: I,
+    pos: usize,
+    last_end: Option<usize>,
+    _marker: core::marker::PhantomData<&'h ()>,
+}
+
+impl<'h, I, R> Scanner<'h, I, R>
+where
+    R: Regex,
+{
+    /// Returns the offset the next search starts from, relative to the scanned input.
+    pub fn offset(&self) -> usize {
+        self.pos
+    }
+}
+
+impl<'h, I, R> Iterator for Scanner<'h, I, R>
+where
+    I: Stream + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+{
+    type Item = (<I as Stream>::Slice, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.pos > self.input.eof_offset() {
+                return None;
+            }
+            let (start, end) =
+                self.re
+                    .captures_read_at(&mut self.locs, self.input.peek_finish(), self.pos)?;
+            if start == end {
+                // Step past an empty match so the search makes progress, and skip one that
+                // directly follows the previous match.
+                self.pos = end + 1;
+                if self.last_end == Some(end) {
+                    continue;
+                }
+            } else {
+                self.pos = end;
+            }
+            self.last_end = Some(end);
+
+            let mut rest = self.input.clone();
+            rest.next_slice(start);
+            return Some((rest.next_slice(end - start), start));
+        }
+    }
+}
+
+/// Returns an iterator over the successive non-overlapping matches of a regex in `input`,
+/// without consuming it.
+///
+/// Each item pairs the matched slice, of the stream's own `Slice` type, with its offset from
+/// the start of `input`. The iterator works on a copy of the stream, so `input` stays where it
+/// is; use the offsets to advance it, or to index a representation of your own. Like
+/// [`find_iter`], matches are found anywhere rather than only at the current position, and
+/// empty matches follow the rules of the `regex` crate, but nothing is sliced or allocated
+/// for capture groups.
+///
+/// On a partial stream, only the current buffer is scanned; matches that could continue into
+/// input not read yet are returned as they are.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::stream::LocatingSlice;
+/// use winnow_regex::scan;
+///
+/// let input = LocatingSlice::new("a=1, bb=22");
+/// let pairs: Vec<_> = scan(r"\w+=\d+", &input).collect();
+/// assert_eq!(pairs, [("a=1", 0), ("bb=22", 5)]);
+/// ```
+pub fn scan<'h, I, Re>(re: Re, input: &I) -> Scanner<'h, I, Re::Output>
+where
+    I: Stream + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    Re::Error: Debug,
+{
+    let re = re.into_regex();
+
+    Scanner {
+        locs: re.capture_locations(),
+        re,
+        input: input.clone(),
+        pos: 0,
+        last_end: None,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+/// The error attached by [`regex_transformed`] when the transform changes the length of the
+/// input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("`regex_transformed` transform must preserve the length")]
+pub struct LengthChangedError;
+
+pub struct TransformedParser<I, R, F, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    transform: F,
+    _marker: core::marker::PhantomData<(I, E)>,
+}
+
+impl<I, R, F, H, T, E> Parser<I, <I as Stream>::Slice, E> for TransformedParser<I, R, F, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone + Debug,
+    <I as Stream>::Slice: AsRef<H>,
+    H: ?Sized + AsRef<[u8]>,
+    R: for<'a> Regex<Haystack<'a> = &'a H>,
+    F: FnMut(&H) -> T,
+    T: AsRef<H>,
+    E: ParserError<I> + FromExternalError<I, LengthChangedError>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<<I as Stream>::Slice, E> {
+        let haystack = input.peek_finish();
+        let transformed = (self.transform)(haystack.as_ref());
+        if transformed.as_ref().as_ref().len() != haystack.as_ref().as_ref().len() {
+            return Err(E::from_external_error(input, LengthChangedError));
+        }
+
+        let found = match self.re.find(transformed.as_ref()) {
+            Some((0, end)) => Some((end, ())),
+            _ => None,
+        };
+        if <I as StreamIsPartial>::is_partial_supported() {
+            consume_match::<_, _, _, true>(input, found)
+        } else {
+            consume_match::<_, _, _, false>(input, found)
+        }
+        .map(|(slice, _)| slice)
+    }
+}
+
+/// Like [`regex()`], but matches against a transformed copy of the input.
+///
+/// `transform` receives the remaining input and returns the text the regex is run on, for
+/// normalizations the regex flags cannot express. The match is then consumed from the
+/// **original** input, so the returned slice keeps the original text.
+///
+/// Offsets found in the transformed text are applied to the original one, which is only sound
+/// if the transform maps every byte to a byte at the same position, like
+/// [`str::to_ascii_lowercase`]. When the transform changes the length, as Unicode case mapping
+/// does for some characters such as `İ`, the parser fails with a [`LengthChangedError`]
+/// attached through [`FromExternalError`]. Transforms that keep the length but move text
+/// around are not detected and produce meaningless slices.
+///
+/// The whole remaining input is transformed on every call, so this is best kept to short
+/// inputs or tokens.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::regex_transformed;
+///
+/// fn keyword<'i>(s: &mut &'i str) -> ModalResult<&'i str> {
+///     regex_transformed(r"^select\b", str::to_ascii_lowercase).parse_next(s)
+/// }
+///
+/// assert_eq!(keyword.parse_peek("SeLeCt *"), Ok((" *", "SeLeCt")));
+/// ```
+#[inline(always)]
+pub fn regex_transformed<Input, Re, F, Error>(
+    re: Re,
+    transform: F,
+) -> TransformedParser<Input, Re::Output, F, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex,
+    Re::Error: Debug,
+    Error: ParserError<Input> + FromExternalError<Input, LengthChangedError> + 'static,
+{
+    let re = re.into_regex();
+
+    TransformedParser {
+        re,
+        transform,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+pub struct EofFlagParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> Parser<I, (<I as Stream>::Slice, bool), E> for EofFlagParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<(<I as Stream>::Slice, bool), E> {
+        let found = match self.re.find(input.peek_finish()) {
+            Some((0, end)) => Some((end, ())),
+            _ => None,
+        };
+        let at_eof = matches!(found, Some((end, _)) if end == input.eof_offset());
+        if <I as StreamIsPartial>::is_partial_supported() {
+            consume_match::<_, _, _, true>(input, found)
+        } else {
+            consume_match::<_, _, _, false>(input, found)
+        }
+        .map(|(slice, _)| (slice, at_eof))
+    }
+}
+
+/// Like [`regex()`], but also reports whether the match reached the end of the input.
+///
+/// This saves a separate `eof` check for grammars where the last token is treated
+/// differently.
+///
+/// The flag is only meaningful for complete input. On partial input a match that reaches the
+/// end of the buffer returns `Incomplete`, as for [`regex()`], so a successful match always
+/// reports `false`.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::regex_eof_flag;
+///
+/// fn field<'i>(s: &mut &'i str) -> ModalResult<(&'i str, bool)> {
+///     regex_eof_flag(r"^\w+").parse_next(s)
+/// }
+///
+/// assert_eq!(field.parse_peek("a,b"), Ok((",b", ("a", false))));
+/// assert_eq!(field.parse_peek("b"), Ok(("", ("b", true))));
+/// ```
+#[inline(always)]
+pub fn regex_eof_flag<'h, Input, Re, Error>(re: Re) -> EofFlagParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    let re = re.into_regex();
+
+    EofFlagParser {
+        re,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+/// Whether a match returned by [`regex_completeness`] can still change with more input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Completeness {
+    /// The match ends before the end of the available input, so more data cannot change it.
+    Definite,
+    /// The match reaches the end of a partial buffer and may be the prefix of a longer match.
+    MaybePrefix,
+}
+
+pub struct CompletenessParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> Parser<I, (<I as Stream>::Slice, Completeness), E>
+    for CompletenessParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<(<I as Stream>::Slice, Completeness), E> {
+        let found = match self.re.find(input.peek_finish()) {
+            Some((0, end)) => Some((end, ())),
+            _ => None,
+        };
+        match found {
+            Some((end, _))
+                if <I as StreamIsPartial>::is_partial_supported()
+                    && input.is_partial()
+                    && end == input.eof_offset() =>
+            {
+                Ok((input.next_slice(end), Completeness::MaybePrefix))
+            }
+            _ if <I as StreamIsPartial>::is_partial_supported() => {
+                consume_match::<_, _, _, true>(input, found)
+                    .map(|(slice, _)| (slice, Completeness::Definite))
+            }
+            _ => consume_match::<_, _, _, false>(input, found)
+                .map(|(slice, _)| (slice, Completeness::Definite)),
+        }
+    }
+}
+
+/// Like [`regex()`], but on partial input returns a match that reaches the end of the buffer
+/// instead of `Incomplete`, flagged as [`Completeness::MaybePrefix`].
+///
+/// [`regex()`] returns `Incomplete` for such a match, because more data might extend it. This
+/// parser hands the provisional match to the caller instead, which suits interactive parsing
+/// where a REPL wants to show what it has so far. A match that ends before the end of the
+/// buffer, and every match on complete input, is [`Completeness::Definite`]. No match on
+/// partial input still returns `Incomplete`.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow::stream::Partial;
+/// use winnow_regex::{Completeness, regex_completeness};
+///
+/// fn word<'i>(s: &mut Partial<&'i str>) -> ModalResult<(&'i str, Completeness)> {
+///     regex_completeness(r"^\w+").parse_next(s)
+/// }
+///
+/// assert_eq!(
+///     word.parse_peek(Partial::new("let x")),
+///     Ok((Partial::new(" x"), ("let", Completeness::Definite)))
+/// );
+/// assert_eq!(
+///     word.parse_peek(Partial::new("le")),
+///     Ok((Partial::new(""), ("le", Completeness::MaybePrefix)))
+/// );
+/// ```
+#[inline(always)]
+pub fn regex_completeness<'h, Input, Re, Error>(
+    re: Re,
+) -> CompletenessParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    let re = re.into_regex();
+
+    CompletenessParser {
+        re,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+pub struct EofParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> Parser<I, <I as Stream>::Slice, E> for EofParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<<I as Stream>::Slice, E> {
+        if <I as StreamIsPartial>::is_partial_supported() && input.is_partial() {
+            // The end of a partial buffer is not the end of the input.
+            return Err(E::incomplete(input, Needed::Unknown));
+        }
+        let found = match self.re.find(input.peek_finish()) {
+            Some((0, end)) => Some((end, ())),
+            _ => None,
+        };
+        let start = input.checkpoint();
+        let (slice, _) = consume_match::<_, _, E, false>(input, found)?;
+        if input.eof_offset() == 0 {
+            Ok(slice)
+        } else {
+            input.reset(&start);
+            Err(ParserError::from_input(input))
+        }
+    }
+}
+
+/// Creates a parser that matches `re` at the current position and then requires the end of the
+/// input.
+///
+/// This is the plain two-step "match, then check for EOF", without reasoning about how `$` or
+/// `\z` behave in the pattern. If anything is left after the match, the parse fails and the
+/// input is left untouched. Compare [`regex_full`], which puts the end anchor into the pattern
+/// itself.
+///
+/// On partial input this always returns `Incomplete`, since the end of the buffer is not yet
+/// the end of the input.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::regex_eof;
+///
+/// fn number<'i>(s: &mut &'i str) -> ModalResult<&'i str> {
+///     regex_eof(r"^\d+").parse_next(s)
+/// }
+///
+/// assert_eq!(number.parse_peek("123"), Ok(("", "123")));
+/// assert!(number.parse_peek("123 ").is_err());
+/// ```
+#[inline(always)]
+pub fn regex_eof<'h, Input, Re, Error>(re: Re) -> EofParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    let re = re.into_regex();
+
+    EofParser {
+        re,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+#[cfg(feature = "hash")]
+pub struct HashedParser<'h, I, R, S, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    hasher: S,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+#[cfg(feature = "hash")]
+impl<'h, I, R, S, E> Parser<I, (<I as Stream>::Slice, u64), E> for HashedParser<'h, I, R, S, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    <I as Stream>::Slice: AsRef<[u8]>,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    S: core::hash::BuildHasher,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<(<I as Stream>::Slice, u64), E> {
+        use core::hash::Hasher;
+
+        let found = match self.re.find(input.peek_finish()) {
+            Some((0, end)) => Some((end, ())),
+            _ => None,
+        };
+        let (slice, _) = if <I as StreamIsPartial>::is_partial_supported() {
+            consume_match::<_, _, E, true>(input, found)
+        } else {
+            consume_match::<_, _, E, false>(input, found)
+        }?;
+        let mut hasher = self.hasher.build_hasher();
+        hasher.write(slice.as_ref());
+        Ok((slice, hasher.finish()))
+    }
+}
+
+/// Like [`regex()`], but also returns a hash of the matched bytes, for deduplicating tokens.
+///
+/// The hash is computed with [`DefaultHasher`](std::hash::DefaultHasher) from fixed keys, so
+/// equal matches hash equally across parsers and runs of the same program. Use
+/// [`regex_hashed_with`] to plug in a faster or keyed hasher. Matches from `&str` and `&[u8]`
+/// streams with the same bytes hash the same.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::regex_hashed;
+///
+/// fn word<'i>(s: &mut &'i str) -> ModalResult<(&'i str, u64)> {
+///     regex_hashed(r"^\w+").parse_next(s)
+/// }
+///
+/// let (_, (_, a)) = word.parse_peek("token 1").unwrap();
+/// let (_, (_, b)) = word.parse_peek("token 2").unwrap();
+/// assert_eq!(a, b);
+/// ```
+#[cfg(feature = "hash")]
+#[inline(always)]
+pub fn regex_hashed<'h, Input, Re, Error>(
+    re: Re,
+) -> HashedParser<
+    'h,
+    Input,
+    Re::Output,
+    core::hash::BuildHasherDefault<std::hash::DefaultHasher>,
+    Error,
+>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    regex_hashed_with(re, Default::default())
+}
+
+/// Like [`regex_hashed`], but hashes the match with hashers built by `hasher`.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+#[cfg(feature = "hash")]
+#[inline(always)]
+pub fn regex_hashed_with<'h, Input, Re, S, Error>(
+    re: Re,
+    hasher: S,
+) -> HashedParser<'h, Input, Re::Output, S, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    S: core::hash::BuildHasher,
+    Error: ParserError<Input> + 'static,
+{
+    HashedParser {
+        re: re.into_regex(),
+        hasher,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+pub struct CheckpointedParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> Parser<I, (<I as Stream>::Slice, <I as Stream>::Checkpoint), E>
+    for CheckpointedParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(
+        &mut self,
+        input: &mut I,
+    ) -> Result<(<I as Stream>::Slice, <I as Stream>::Checkpoint), E> {
+        let checkpoint = input.checkpoint();
+        let found = match self.re.find(input.peek_finish()) {
+            Some((0, end)) => Some((end, ())),
+            _ => None,
+        };
+        let (slice, _) = if <I as StreamIsPartial>::is_partial_supported() {
+            consume_match::<_, _, E, true>(input, found)
+        } else {
+            consume_match::<_, _, E, false>(input, found)
+        }?;
+        Ok((slice, checkpoint))
+    }
+}
+
+/// Like [`regex()`], but also returns a checkpoint of the input taken before the match.
+///
+/// Passing the checkpoint to [`Stream::reset`] rolls the input back to where the match
+/// started, so a speculative parse can be undone by hand when a later check fails, beyond what
+/// `peek` or `alt` offer. The checkpoint is only valid for the stream it was taken from, and
+/// for that stream at or after the point it was taken; resetting a different stream is a logic
+/// error.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow::stream::Stream;
+/// use winnow_regex::regex_checkpointed;
+///
+/// let mut input = "123abc";
+/// let (digits, checkpoint) = regex_checkpointed::<_, _, winnow::error::ContextError>(r"^\d+")
+///     .parse_next(&mut input)
+///     .unwrap();
+/// assert_eq!((digits, input), ("123", "abc"));
+///
+/// // A later check rejects the number, so undo the match.
+/// input.reset(&checkpoint);
+/// assert_eq!(input, "123abc");
+/// ```
+#[inline(always)]
+pub fn regex_checkpointed<'h, Input, Re, Error>(
+    re: Re,
+) -> CheckpointedParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    let re = re.into_regex();
+
+    CheckpointedParser {
+        re,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+pub struct ViaHaystackParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> Parser<I, <I as Stream>::Slice, E> for ViaHaystackParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    <I as Stream>::Slice: IntoHaystack<'h, Haystack = R::Haystack<'h>>,
+    R: Regex,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<<I as Stream>::Slice, E> {
+        let found = match self.re.find(input.peek_finish().into_haystack()) {
+            Some((0, end)) => Some((end, ())),
+            _ => None,
+        };
+        let (slice, _) = if <I as StreamIsPartial>::is_partial_supported() {
+            consume_match::<_, _, E, true>(input, found)
+        } else {
+            consume_match::<_, _, E, false>(input, found)
+        }?;
+        Ok(slice)
+    }
+}
+
+/// Like [`regex()`], for streams whose `Slice` is not the regex haystack itself but converts to
+/// it through [`IntoHaystack`].
+///
+/// This lets a stream use a domain newtype such as `LineSlice<'i>(&'i str)` as its slice type:
+/// the regex runs on the converted `&str` or `&[u8]`, and the match is returned as the stream's
+/// own slice, at the same offsets. The partial-input rules of [`regex()`] apply.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::{regex_trait::IntoHaystack, regex_via_haystack};
+/// # use core::fmt::Debug;
+/// # use winnow::error::Needed;
+/// # use winnow::stream::{Offset, Stream, StreamIsPartial};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq)]
+/// struct LineSlice<'i>(&'i str);
+///
+/// impl<'i> IntoHaystack<'i> for LineSlice<'i> {
+///     type Haystack = &'i str;
+///
+///     fn into_haystack(self) -> &'i str {
+///         self.0
+///     }
+/// }
+///
+/// /// A stream over `&str` whose slices are `LineSlice`s.
+/// #[derive(Debug, Clone, Copy, PartialEq)]
+/// struct Lines<'i>(&'i str);
+///
+/// // `impl Stream for Lines` delegates to `&str` and wraps each slice in `LineSlice`.
+/// # impl Offset for Lines<'_> {
+/// #     fn offset_from(&self, start: &Self) -> usize {
+/// #         self.0.offset_from(&start.0)
+/// #     }
+/// # }
+/// #
+/// # impl StreamIsPartial for Lines<'_> {
+/// #     type PartialState = ();
+/// #
+/// #     fn complete(&mut self) {}
+/// #
+/// #     fn restore_partial(&mut self, _state: ()) {}
+/// #
+/// #     fn is_partial_supported() -> bool {
+/// #         false
+/// #     }
+/// # }
+/// #
+/// # impl<'i> Stream for Lines<'i> {
+/// #     type Token = char;
+/// #     type Slice = LineSlice<'i>;
+/// #     type IterOffsets = core::str::CharIndices<'i>;
+/// #     type Checkpoint = Self;
+/// #
+/// #     fn iter_offsets(&self) -> Self::IterOffsets {
+/// #         self.0.char_indices()
+/// #     }
+/// #
+/// #     fn eof_offset(&self) -> usize {
+/// #         self.0.len()
+/// #     }
+/// #
+/// #     fn next_token(&mut self) -> Option<char> {
+/// #         self.0.next_token()
+/// #     }
+/// #
+/// #     fn peek_token(&self) -> Option<char> {
+/// #         self.0.peek_token()
+/// #     }
+/// #
+/// #     fn offset_for<P: Fn(char) -> bool>(&self, predicate: P) -> Option<usize> {
+/// #         self.0.offset_for(predicate)
+/// #     }
+/// #
+/// #     fn offset_at(&self, tokens: usize) -> Result<usize, Needed> {
+/// #         self.0.offset_at(tokens)
+/// #     }
+/// #
+/// #     fn next_slice(&mut self, offset: usize) -> LineSlice<'i> {
+/// #         LineSlice(self.0.next_slice(offset))
+/// #     }
+/// #
+/// #     fn peek_slice(&self, offset: usize) -> LineSlice<'i> {
+/// #         LineSlice(self.0.peek_slice(offset))
+/// #     }
+/// #
+/// #     fn checkpoint(&self) -> Self {
+/// #         *self
+/// #     }
+/// #
+/// #     fn reset(&mut self, checkpoint: &Self) {
+/// #         *self = *checkpoint;
+/// #     }
+/// #
+/// #     fn raw(&self) -> &dyn Debug {
+/// #         self
+/// #     }
+/// # }
+///
+/// fn key<'i>(input: &mut Lines<'i>) -> ModalResult<LineSlice<'i>> {
+///     regex_via_haystack(r"^\w+").parse_next(input)
+/// }
+///
+/// let mut input = Lines("key value");
+/// assert_eq!(key.parse_next(&mut input), Ok(LineSlice("key")));
+/// assert_eq!(input, Lines(" value"));
+/// assert!(key.parse_next(&mut input).is_err());
+/// ```
+#[inline(always)]
+pub fn regex_via_haystack<'h, Input, Re, Error>(
+    re: Re,
+) -> ViaHaystackParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    <Input as Stream>::Slice: IntoHaystack<'h, Haystack = <Re::Output as Regex>::Haystack<'h>>,
+    Re: RegexPattern,
+    Re::Output: Regex,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    let re = re.into_regex();
+
+    ViaHaystackParser {
+        re,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+/// A symbol table that [`regex_intern`] inserts matched tokens into.
+pub trait Interner {
+    /// The handle returned for an interned token, typically a small integer.
+    type Symbol;
+
+    /// Returns the symbol for `token`, inserting it if it has not been seen before.
+    ///
+    /// Interning the same token twice must return equal symbols.
+    fn intern(&mut self, token: &str) -> Self::Symbol;
+}
+
+impl<N: Interner + ?Sized> Interner for &mut N {
+    type Symbol = N::Symbol;
+
+    #[inline(always)]
+    fn intern(&mut self, token: &str) -> Self::Symbol {
+        (**self).intern(token)
+    }
+}
+
+pub struct InternParser<'h, 'a, I, R, N, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    N: Interner + ?Sized,
+    E: ParserError<I>,
+{
+    re: R,
+    interner: &'a mut N,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, N, E> Parser<I, N::Symbol, E> for InternParser<'h, '_, I, R, N, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    <I as Stream>::Slice: AsRef<str>,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    N: Interner + ?Sized,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<N::Symbol, E> {
+        let found = match self.re.find(input.peek_finish()) {
+            Some((0, end)) => Some((end, ())),
+            _ => None,
+        };
+        let (slice, _) = if <I as StreamIsPartial>::is_partial_supported() {
+            consume_match::<_, _, E, true>(input, found)
+        } else {
+            consume_match::<_, _, E, false>(input, found)
+        }?;
+        Ok(self.interner.intern(slice.as_ref()))
+    }
+}
+
+/// Like [`regex()`], but interns the matched token into `interner` and returns its symbol.
+///
+/// Lexers that intern identifiers can hand out cheap, comparable symbols instead of keeping
+/// borrowed slices alive. The interner is borrowed mutably for as long as the parser lives, so
+/// drop the parser (or let it go out of scope) before inspecting the table.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use winnow::prelude::*;
+/// use winnow_regex::{Interner, regex_intern};
+///
+/// #[derive(Default)]
+/// struct Symbols(HashMap<String, u32>);
+///
+/// impl Interner for Symbols {
+///     type Symbol = u32;
+///
+///     fn intern(&mut self, token: &str) -> u32 {
+///         let next = self.0.len() as u32;
+///         *self.0.entry(token.to_owned()).or_insert(next)
+///     }
+/// }
+///
+/// let mut symbols = Symbols::default();
+/// let mut ident = regex_intern::<_, _, _, winnow::error::ContextError>(r"^\w+\s*", &mut symbols);
+/// assert_eq!(ident.parse_peek("foo bar"), Ok(("bar", 0)));
+/// assert_eq!(ident.parse_peek("bar"), Ok(("", 1)));
+/// assert_eq!(ident.parse_peek("foo "), Ok(("", 0)));
+/// ```
+#[inline(always)]
+pub fn regex_intern<'h, 'a, Input, Re, N, Error>(
+    re: Re,
+    interner: &'a mut N,
+) -> InternParser<'h, 'a, Input, Re::Output, N, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    <Input as Stream>::Slice: AsRef<str>,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    N: Interner + ?Sized,
+    Error: ParserError<Input> + 'static,
+{
+    let re = re.into_regex();
+
+    InternParser {
+        re,
+        interner,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+/// The named groups of a match, produced by [`captures_named_only`].
+///
+/// Only the spans of named groups are stored; anonymous groups are not reachable. Groups are
+/// looked up by name and ranges are relative to the match, like in [`Captures`].
+pub struct NamedCaptures<Slice, H>
+where
+    H: ?Sized,
+{
+    slice: Slice,
+    names: Arc<[Box<str>]>,
+    spans: Box<[Option<(usize, usize)>]>,
+    _marker: core::marker::PhantomData<fn(&H)>,
+}
+
+impl<Slice, H> NamedCaptures<Slice, H>
+where
+    Slice: AsRef<H>,
+    H: ?Sized + Index<Range<usize>, Output = H>,
+{
+    /// Returns the number of named groups in the pattern.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Returns `true` if the pattern has no named groups.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Returns the whole match.
+    pub fn as_match(&self) -> &Slice {
+        &self.slice
+    }
+
+    /// Returns the byte range of the group named `name` within the match, or `None` if there is
+    /// no such group or it did not participate.
+    pub fn range(&self, name: &str) -> Option<Range<usize>> {
+        let i = self.names.iter().position(|group| &**group == name)?;
+        let (start, end) = self.spans[i]?;
+        Some(start..end)
+    }
+
+    /// Returns the text of the group named `name`, or `None` if there is no such group or it
+    /// did not participate.
+    pub fn get(&self, name: &str) -> Option<&H> {
+        Some(&self.slice.as_ref()[self.range(name)?])
+    }
+
+    /// Iterates over the named groups in pattern order, like [`Captures::named_iter`].
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (&str, Option<&H>)> {
+        self.names
+            .iter()
+            .zip(self.spans.iter())
+            .map(|(name, span)| {
+                let text = span.map(|(start, end)| &self.slice.as_ref()[start..end]);
+                (&**name, text)
+            })
+    }
+}
+
+pub struct NamedOnlyParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    locs: R::CaptureLocations,
+    names: Arc<[Box<str>]>,
+    groups: Box<[usize]>,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E>
+    Parser<
+        I,
+        NamedCaptures<<I as Stream>::Slice, <R::CaptureLocations as CaptureLocations>::Input>,
+        E,
+    > for NamedOnlyParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(
+        &mut self,
+        input: &mut I,
+    ) -> Result<
+        NamedCaptures<<I as Stream>::Slice, <R::CaptureLocations as CaptureLocations>::Input>,
+        E,
+    > {
+        let slice = if <I as StreamIsPartial>::is_partial_supported() {
+            captures_into::<_, _, E, true>(input, &self.re, &mut self.locs)
+        } else {
+            captures_into::<_, _, E, false>(input, &self.re, &mut self.locs)
+        }?;
+        Ok(NamedCaptures {
+            slice,
+            names: self.names.clone(),
+            spans: self.groups.iter().map(|&i| self.locs.get(i)).collect(),
+            _marker: core::marker::PhantomData,
+        })
+    }
+}
+
+/// Like [`captures`], but keeps only the named groups, looked up by name.
+///
+/// Meant for record parsers that address fields exclusively by name: the result stores one span
+/// per named group and nothing for anonymous ones.
+///
+/// How much work this saves depends on the backend. The `regex` crate engines resolve every
+/// group during the search regardless, so here this is a filtered view that only saves the
+/// per-match storage of the anonymous groups. With the `automata` feature,
+/// `automata::captures_slots` given the named group
+/// indices lets the engine stop tracking slots after the last named group, which reduces the
+/// search work itself when the named groups come first.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::captures_named_only;
+///
+/// let mut record = captures_named_only::<_, _, winnow::error::ContextError>(
+///     r"^(?<key>\w+)(\s*)=(\s*)(?<value>\w+)?",
+/// );
+/// let caps = record.parse_next(&mut "name = ;").unwrap();
+/// assert_eq!(caps.len(), 2);
+/// assert_eq!(caps.get("key"), Some("name"));
+/// assert_eq!(caps.get("value"), None);
+/// ```
+#[inline(always)]
+pub fn captures_named_only<'h, Input, Re, Error>(
+    re: Re,
+) -> NamedOnlyParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    let re = re.into_regex();
+    let (groups, names): (Vec<_>, Vec<_>) = re
+        .capture_names()
+        .enumerate()
+        .filter_map(|(i, name)| Some((i, Box::from(name?))))
+        .unzip();
+
+    NamedOnlyParser {
+        locs: re.capture_locations(),
+        re,
+        names: names.into(),
+        groups: groups.into(),
+        _marker: core::marker::PhantomData,
+    }
+}
+
+pub struct FromStrParser<'h, I, R, T, E = ErrMode<ContextError>>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    inner: RegexParser<'h, I, R, E>,
+    _marker: core::marker::PhantomData<T>,
+}
+
+impl<'h, I, R, T, E> Parser<I, T, E> for FromStrParser<'h, I, R, T, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    <I as Stream>::Slice: AsRef<str>,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    T: FromStr,
+    E: ParserError<I> + FromExternalError<I, T::Err>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<T, E> {
+        let start = input.checkpoint();
+        let slice = self.inner.parse_next(input)?;
+        slice.as_ref().parse().map_err(|e| {
+            input.reset(&start);
+            E::from_external_error(input, e)
+        })
+    }
+}
+
+/// Creates a parser that matches a regex and converts the whole match with [`FromStr`].
+///
+/// This is the whole-match counterpart of [`map_group`], for the common case of matching a
+/// token and parsing it without any capture groups. A `from_str` error is reported through
+/// [`FromExternalError`] and leaves the input untouched. For byte streams, see
+/// [`bytes::regex_parse`].
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::regex_parse;
+///
+/// fn int(s: &mut &str) -> ModalResult<i64> {
+///     regex_parse(r"^-?\d+").parse_next(s)
+/// }
+///
+/// assert_eq!(int.parse_peek("-42,"), Ok((",", -42)));
+/// // The token matches, but does not fit in an `i64`.
+/// assert!(int.parse_peek("99999999999999999999").is_err());
+/// ```
+#[inline(always)]
+pub fn regex_parse<'h, Input, Re, T, Error>(
+    re: Re,
+) -> FromStrParser<'h, Input, Re::Output, T, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    T: FromStr,
+    Error: ParserError<Input> + FromExternalError<Input, T::Err> + 'static,
+{
+    FromStrParser {
+        inner: regex(re),
+        _marker: core::marker::PhantomData,
+    }
+}
+
+pub struct ParticipationParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    locs: R::CaptureLocations,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> Parser<I, (<I as Stream>::Slice, Vec<usize>), E>
+    for ParticipationParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<(<I as Stream>::Slice, Vec<usize>), E> {
+        let slice = if <I as StreamIsPartial>::is_partial_supported() {
+            captures_into::<_, _, E, true>(input, &self.re, &mut self.locs)
+        } else {
+            captures_into::<_, _, E, false>(input, &self.re, &mut self.locs)
+        }?;
+        let groups = (0..self.locs.len())
+            .filter(|&i| self.locs.get(i).is_some())
+            .collect();
+        Ok((slice, groups))
+    }
+}
+
+/// Creates a parser that matches a regex and lists the indices of the groups that participated.
+///
+/// The indices are in ascending order and always start with `0` for the whole match. This shows
+/// exactly which optional groups fired, e.g. to check that an allowed combination of optional
+/// fields was present, without holding on to the [`Captures`].
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::captures_participation;
+///
+/// fn fields<'i>(s: &mut &'i str) -> ModalResult<(&'i str, Vec<usize>)> {
+///     captures_participation(r"^(\w+)(?::(\d+))?(?:@(\w+))?").parse_next(s)
+/// }
+///
+/// assert_eq!(fields.parse_peek("host@eu;"), Ok((";", ("host@eu", vec![0, 1, 3]))));
+/// assert_eq!(fields.parse_peek("host:80;"), Ok((";", ("host:80", vec![0, 1, 2]))));
+/// ```
+#[inline(always)]
+pub fn captures_participation<'h, Input, Re, Error>(
+    re: Re,
+) -> ParticipationParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    let re = re.into_regex();
+
+    ParticipationParser {
+        locs: re.capture_locations(),
+        re,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+/// The error attached by [`captures_unescaped`] when the group holds a malformed escape.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("invalid escape sequence at byte {offset}")]
+pub struct InvalidEscape {
+    offset: usize,
+}
+
+impl InvalidEscape {
+    /// Returns the offset of the offending backslash within the group.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+type UnescapeFn = fn(&str) -> Result<String, InvalidEscape>;
+
+/// Resolves the C-style escapes `\n`, `\t`, `\r`, `\0`, `\\`, `\"` and `\'`.
+fn unescape(text: &str) -> Result<String, InvalidEscape> {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.char_indices();
+    while let Some((_, c)) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        let offset = text.len() - chars.as_str().len() - 1;
+        out.push(match chars.next() {
+            Some((_, 'n')) => '\n',
+            Some((_, 't')) => '\t',
+            Some((_, 'r')) => '\r',
+            Some((_, '0')) => '\0',
+            Some((_, c @ ('\\' | '"' | '\''))) => c,
+            _ => return Err(InvalidEscape { offset }),
+        });
+    }
+    Ok(out)
+}
+
+/// Creates a parser that matches a regex and returns group `group` with its escapes resolved.
+///
+/// This is [`map_group`] with a built-in unescaper for the usual C-style escapes: `\n`, `\t`,
+/// `\r`, `\0`, `\\`, `\"` and `\'`. Any other escape, or a trailing backslash, is reported as
+/// an [`InvalidEscape`] through [`FromExternalError`], leaving the input untouched. Like in
+/// [`map_group`], a group that did not participate fails the parse like a non-matching regex.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::captures_unescaped;
+///
+/// fn string(s: &mut &str) -> ModalResult<String> {
+///     captures_unescaped(r#"^"((?:[^"\\]|\\.)*)""#, 1).parse_next(s)
+/// }
+///
+/// assert_eq!(string.parse_peek(r#""say \"hi\"\n";"#), Ok((";", "say \"hi\"\n".to_owned())));
+/// assert!(string.parse_peek(r#""\q""#).is_err());
+/// ```
+#[inline(always)]
+pub fn captures_unescaped<'h, Input, Re, Error>(
+    re: Re,
+    group: usize,
+) -> MapGroupParser<'h, Input, Re::Output, UnescapeFn, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    <Re::Output as Regex>::CaptureLocations: CaptureLocations<Input = str>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + FromExternalError<Input, InvalidEscape> + 'static,
+{
+    map_group(re, group, unescape as UnescapeFn)
+}
+
+pub struct NewlineCountParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> Parser<I, (<I as Stream>::Slice, usize), E> for NewlineCountParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    <I as Stream>::Slice: AsRef<[u8]>,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<(<I as Stream>::Slice, usize), E> {
+        let found = match self.re.find(input.peek_finish()) {
+            Some((0, end)) => Some((end, ())),
+            _ => None,
+        };
+        let (slice, _) = if <I as StreamIsPartial>::is_partial_supported() {
+            consume_match::<_, _, E, true>(input, found)
+        } else {
+            consume_match::<_, _, E, false>(input, found)
+        }?;
+        let newlines = slice.as_ref().iter().filter(|&&b| b == b'\n').count();
+        Ok((slice, newlines))
+    }
+}
+
+/// Like [`regex()`], but also returns the number of `\n` bytes in the match.
+///
+/// Parsers that track line numbers by hand, without a [`LocatingSlice`], can add the count to
+/// their line counter as they consume multi-line tokens such as block comments or raw strings,
+/// instead of scanning the match again.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::regex_newline_count;
+///
+/// fn comment<'i>(s: &mut &'i str) -> ModalResult<(&'i str, usize)> {
+///     regex_newline_count(r"^/\*(?s:.*?)\*/").parse_next(s)
+/// }
+///
+/// assert_eq!(comment.parse_peek("/* a\n b\n */x"), Ok(("x", ("/* a\n b\n */", 2))));
+/// assert_eq!(comment.parse_peek("/**/x"), Ok(("x", ("/**/", 0))));
+/// ```
+#[inline(always)]
+pub fn regex_newline_count<'h, Input, Re, Error>(
+    re: Re,
+) -> NewlineCountParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    let re = re.into_regex();
+
+    NewlineCountParser {
+        re,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+pub struct GroupThenParser<'h, I, R, P, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    locs: R::CaptureLocations,
+    group: usize,
+    inner: P,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, P, O, E> Parser<I, O, E> for GroupThenParser<'h, I, R, P, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    <I as Stream>::Slice: Stream,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    P: Parser<<I as Stream>::Slice, O, E>,
+    E: ParserError<I> + ParserError<<I as Stream>::Slice>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<O, E> {
+        let start = input.checkpoint();
+        if <I as StreamIsPartial>::is_partial_supported() {
+            captures_into::<_, _, E, true>(input, &self.re, &mut self.locs)
+        } else {
+            captures_into::<_, _, E, false>(input, &self.re, &mut self.locs)
+        }?;
+
+        let Some(span) = self.locs.get(self.group) else {
+            input.reset(&start);
+            return Err(ParserError::from_input(input));
+        };
+        let mut group = slice_at(input, &start, span);
+        match self.inner.parse_next(&mut group) {
+            Ok(output) if group.eof_offset() == 0 => Ok(output),
+            Ok(_) => {
+                input.reset(&start);
+                Err(ParserError::from_input(input))
+            }
+            Err(e) => {
+                input.reset(&start);
+                Err(e)
+            }
+        }
+    }
+}
+
+/// Creates a parser that matches a regex, then runs `inner` on the text of group `group`.
+///
+/// This splits parsing in two stages: the regex finds the coarse structure, and a winnow
+/// parser does the precise parse of one field. `inner` sees only the group's slice and must
+/// consume all of it. Its output is returned and the whole regex match is consumed.
+///
+/// `inner` shares the error type of the outer parser, so its errors are passed through as they
+/// are, e.g. a cut stays a cut. Leftover input in the group, or a group that did not
+/// participate, fails like a non-matching regex. On any error the input is left untouched.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::ascii::dec_uint;
+/// use winnow::prelude::*;
+/// use winnow_regex::group_then;
+///
+/// fn value(s: &mut &str) -> ModalResult<u32> {
+///     group_then(r"^\w+=\(([^)]*)\)", 1, dec_uint).parse_next(s)
+/// }
+///
+/// assert_eq!(value.parse_peek("key=(42);"), Ok((";", 42)));
+/// // The group does not hold just a number.
+/// assert!(value.parse_peek("key=(42x);").is_err());
+/// ```
+#[inline(always)]
+pub fn group_then<'h, Input, Re, P, O, Error>(
+    re: Re,
+    group: usize,
+    inner: P,
+) -> GroupThenParser<'h, Input, Re::Output, P, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    <Input as Stream>::Slice: Stream,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    P: Parser<<Input as Stream>::Slice, O, Error>,
+    Error: ParserError<Input> + ParserError<<Input as Stream>::Slice> + 'static,
+{
+    let re = re.into_regex();
+
+    GroupThenParser {
+        locs: re.capture_locations(),
+        re,
+        group,
+        inner,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+pub struct TrimParser<'h, I, R, F, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    keep: F,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, F, E> Parser<I, <I as Stream>::Slice, E> for TrimParser<'h, I, R, F, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    <I as Stream>::Slice: AsRef<<R::CaptureLocations as CaptureLocations>::Input>,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    F: FnMut(&<R::CaptureLocations as CaptureLocations>::Input) -> usize,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<<I as Stream>::Slice, E> {
+        let start = input.checkpoint();
+        let found = match self.re.find(input.peek_finish()) {
+            Some((0, end)) => Some((end, ())),
+            _ => None,
+        };
+        let (slice, _) = if <I as StreamIsPartial>::is_partial_supported() {
+            consume_match::<_, _, E, true>(input, found)
+        } else {
+            consume_match::<_, _, E, false>(input, found)
+        }?;
+
+        let len = input.offset_from(&start);
+        let keep = (self.keep)(slice.as_ref()).min(len);
+        input.reset(&start);
+        Ok(input.next_slice(keep))
+    }
+}
+
+/// Creates a parser that matches a regex, then keeps only a prefix of the match chosen by
+/// `keep`.
+///
+/// `keep` receives the whole match and returns how many bytes of it to consume; the rest is
+/// left in the input, and the kept prefix is returned. This handles boundaries a regex cannot
+/// express, e.g. a run of characters that must be cut at a semantic limit. A value larger than
+/// the match is clamped to the whole match. On partial input, a match reaching the end of the
+/// buffer still returns `Incomplete`, since the regex might match further.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile, or if `keep` returns an offset inside a
+/// UTF-8 character of a `str` match.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::regex_trim;
+///
+/// // Hex digits come in pairs; an odd one out is left for the next parser.
+/// fn hex_bytes<'i>(s: &mut &'i str) -> ModalResult<&'i str> {
+///     regex_trim(r"^[[:xdigit:]]+", |m: &str| m.len() / 2 * 2).parse_next(s)
+/// }
+///
+/// assert_eq!(hex_bytes.parse_peek("c0ffee1;"), Ok(("1;", "c0ffee")));
+/// ```
+#[inline(always)]
+pub fn regex_trim<'h, Input, Re, F, Error>(
+    re: Re,
+    keep: F,
+) -> TrimParser<'h, Input, Re::Output, F, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    F: FnMut(&<<Re::Output as Regex>::CaptureLocations as CaptureLocations>::Input) -> usize,
+    Error: ParserError<Input> + 'static,
+{
+    let re = re.into_regex();
+
+    TrimParser {
+        re,
+        keep,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+/// A table of named regexes compiled once, used as a lexer yielding `(name, slice)`.
+///
+/// `&LexerTable` is a parser: like [`any_of`], it tries the regexes in table order and
+/// consumes the first match, returning the name of the entry that matched. List keywords
+/// before the identifier pattern that would also match them, or use [`longest_match`] when the
+/// longest token should win. On partial input the rules of [`regex()`] apply to the first regex
+/// that matches.
+///
+/// # Example
+///
+/// ```
+/// use winnow::combinator::{repeat, terminated};
+/// use winnow::prelude::*;
+/// use winnow_regex::{LexerTable, regex};
+///
+/// let table = LexerTable::new([
+///     ("keyword", r"^(?:let|in)\b"),
+///     ("number", r"^\d+"),
+///     ("ident", r"^\w+"),
+/// ])
+/// .unwrap();
+/// assert_eq!(table.index_of("number"), Some(1));
+///
+/// fn tokens<'t, 'i>(table: &'t LexerTable, s: &mut &'i str) -> ModalResult<Vec<(&'t str, &'i str)>> {
+///     repeat(0.., terminated(table, regex(r"^\s*"))).parse_next(s)
+/// }
+///
+/// assert_eq!(
+///     tokens(&table, &mut "let x1 in 42").unwrap(),
+///     [("keyword", "let"), ("ident", "x1"), ("keyword", "in"), ("number", "42")]
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct LexerTable<R = regex::Regex> {
+    names: Vec<Box<str>>,
+    res: Vec<R>,
+    index: HashMap<Box<str>, usize>,
+}
+
+#[allow(clippy::len_without_is_empty)]
+impl<R: Regex> LexerTable<R> {
+    /// Compiles every `(name, pattern)` pair in order, returning the first compile error.
+    ///
+    /// Patterns may be anything implementing [`RegexPattern`], such as `&str` or precompiled
+    /// regexes. If a name repeats, [`LexerTable::index_of`] returns its first entry.
+    pub fn new<'n, P>(patterns: impl IntoIterator<Item = (&'n str, P)>) -> Result<Self, Error>
+    where
+        P: RegexPattern<Output = R, Error = Error>,
+    {
+        let mut table = Self {
+            names: Vec::new(),
+            res: Vec::new(),
+            index: HashMap::new(),
+        };
+        for (name, pattern) in patterns {
+            table.index.entry(name.into()).or_insert(table.res.len());
+            table.names.push(name.into());
+            table.res.push(pattern.try_into_regex()?);
+        }
+        Ok(table)
+    }
+
+    /// Returns the number of entries in the table.
+    pub fn len(&self) -> usize {
+        self.res.len()
+    }
+
+    /// Returns the index of the entry named `name`.
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.index.get(name).copied()
+    }
+
+    /// Returns the name of entry `index`.
+    pub fn name(&self, index: usize) -> Option<&str> {
+        self.names.get(index).map(|name| &**name)
+    }
+}
+
+impl<'t, 'h, I, R, E> Parser<I, (&'t str, <I as Stream>::Slice), E> for &'t LexerTable<R>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<(&'t str, <I as Stream>::Slice), E> {
+        let (index, slice) = first_match(input, &self.res, false)?;
+        let table: &'t LexerTable<R> = self;
+        Ok((&table.names[index], slice))
+    }
+}
+
+pub struct EndOffsetParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> Parser<I, (<I as Stream>::Slice, usize), E> for EndOffsetParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<(<I as Stream>::Slice, usize), E> {
+        let start = input.checkpoint();
+        let found = match self.re.find(input.peek_finish()) {
+            Some((0, end)) => Some((end, ())),
+            _ => None,
+        };
+        let (slice, _) = if <I as StreamIsPartial>::is_partial_supported() {
+            consume_match::<_, _, E, true>(input, found)
+        } else {
+            consume_match::<_, _, E, false>(input, found)
+        }?;
+        Ok((slice, input.offset_from(&start)))
+    }
+}
+
+/// Like [`regex()`], but also returns the offset just past the match.
+///
+/// The offset is measured with the stream's [`Offset`] implementation from a checkpoint taken
+/// where this parser started, so it is relative to the input position before the match, and
+/// equals the number of bytes (tokens, for token streams) consumed. Add it to a position
+/// counter kept alongside the stream to track positions without wrapping it in a
+/// [`LocatingSlice`]; for absolute positions, use a `LocatingSlice` and
+/// [`Location::current_token_start`] instead.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::regex_end_offset;
+///
+/// let mut input = "héllo world";
+/// let mut pos = 0;
+/// let (word, end) = regex_end_offset::<_, _, winnow::error::ContextError>(r"^\w+ ")
+///     .parse_next(&mut input)
+///     .unwrap();
+/// pos += end;
+/// assert_eq!((word, pos, input), ("héllo ", 7, "world"));
+/// ```
+#[inline(always)]
+pub fn regex_end_offset<'h, Input, Re, Error>(
+    re: Re,
+) -> EndOffsetParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    let re = re.into_regex();
+
+    EndOffsetParser {
+        re,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+/// A capture group referred to by index or by name, as accepted by [`captures_or`].
+pub trait GroupKey {
+    /// Returns the index of the group in `re`, or `None` if it has no such group.
+    fn group_index<R: Regex>(&self, re: &R) -> Option<usize>;
+}
+
+impl GroupKey for usize {
+    fn group_index<R: Regex>(&self, re: &R) -> Option<usize> {
+        (*self < re.capture_names().count()).then_some(*self)
+    }
+}
+
+impl GroupKey for &str {
+    fn group_index<R: Regex>(&self, re: &R) -> Option<usize> {
+        re.capture_names().position(|name| name == Some(*self))
+    }
+}
+
+pub struct CapturesOrParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    locs: R::CaptureLocations,
+    defaults: Vec<(usize, <I as Stream>::Slice)>,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> Parser<I, Vec<<I as Stream>::Slice>, E> for CapturesOrParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    <I as Stream>::Slice: Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<Vec<<I as Stream>::Slice>, E> {
+        let start = input.checkpoint();
+        if <I as StreamIsPartial>::is_partial_supported() {
+            captures_into::<_, _, E, true>(input, &self.re, &mut self.locs)
+        } else {
+            captures_into::<_, _, E, false>(input, &self.re, &mut self.locs)
+        }?;
+        Ok(self
+            .defaults
+            .iter()
+            .map(|(group, default)| match self.locs.get(*group) {
+                Some(span) => slice_at(input, &start, span),
+                None => default.clone(),
+            })
+            .collect())
+    }
+}
+
+/// Creates a parser that matches a regex and returns the listed groups, substituting a default
+/// for each group that did not participate.
+///
+/// `defaults` pairs each group, by index or by name, with the value to use when it is missing.
+/// The result holds one slice per listed group, in the order given, so optional fields with
+/// known defaults need no `unwrap_or` at the call site. The defaults have the stream's slice
+/// type, e.g. `&str` literals for string input or `&[u8]` for byte input.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile, or if a listed group does not exist in it.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::captures_or;
+///
+/// fn ratio<'i>(s: &mut &'i str) -> ModalResult<Vec<&'i str>> {
+///     captures_or(r"^(?<num>\d+)(?:/(?<den>\d+))?", [("num", ""), ("den", "1")]).parse_next(s)
+/// }
+///
+/// assert_eq!(ratio.parse_peek("3/4;"), Ok((";", vec!["3", "4"])));
+/// assert_eq!(ratio.parse_peek("3;"), Ok((";", vec!["3", "1"])));
+/// ```
+#[inline(always)]
+pub fn captures_or<'h, Input, Re, K, Error>(
+    re: Re,
+    defaults: impl IntoIterator<Item = (K, <Input as Stream>::Slice)>,
+) -> CapturesOrParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    K: GroupKey + Debug,
+    Error: ParserError<Input> + 'static,
+{
+    let re = re.into_regex();
+    let defaults = defaults
+        .into_iter()
+        .map(|(key, default)| match key.group_index(&re) {
+            Some(group) => (group, default),
+            None => panic!("no capture group {:?} in regex for captures_or", key),
+        })
+        .collect();
+
+    CapturesOrParser {
+        locs: re.capture_locations(),
+        re,
+        defaults,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+pub struct SpanParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    inner: RegexParser<'h, I, R, E>,
+}
+
+impl<'h, I, R, E> Parser<I, (<I as Stream>::Slice, Range<usize>), E> for SpanParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Location + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<(<I as Stream>::Slice, Range<usize>), E> {
+        let start = input.current_token_start();
+        let slice = self.inner.parse_next(input)?;
+        Ok((slice, start..input.previous_token_end()))
+    }
+}
+
+/// Like [`regex()`], but also returns the absolute byte range of the match, for diagnostics.
+///
+/// The input must track its position, e.g. by wrapping it in [`LocatingSlice`], and the range
+/// is measured from the start of that input. A plain `Range<usize>` is what diagnostic crates
+/// take, so no conversion type is needed:
+///
+/// - `codespan-reporting`: `Label::primary(file_id, span)`.
+/// - `ariadne`: `(file_id, span)` implements `ariadne::Span`, and a bare `Range<usize>` does
+///   too for single-file reports.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::combinator::preceded;
+/// use winnow::prelude::*;
+/// use winnow::stream::LocatingSlice;
+/// use winnow_regex::regex_span;
+///
+/// fn name<'i>(s: &mut LocatingSlice<&'i str>) -> ModalResult<(&'i str, std::ops::Range<usize>)> {
+///     preceded("let ", regex_span(r"^\w+")).parse_next(s)
+/// }
+///
+/// let (ident, span) = name.parse_next(&mut LocatingSlice::new("let x1 = 2;")).unwrap();
+/// assert_eq!((ident, span), ("x1", 4..6));
+/// ```
+#[inline(always)]
+pub fn regex_span<'h, Input, Re, Error>(re: Re) -> SpanParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Location + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    SpanParser { inner: regex(re) }
+}
+
+pub struct TokenizeParser<'h, I, K, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    kinds: Vec<K>,
+    res: Vec<R>,
+    longest: bool,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, K, R, E> TokenizeParser<'h, I, K, R, E>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    /// Picks the longest match instead of the first one, like [`longest_match`].
+    ///
+    /// Matches of equal length still go to the entry listed first, so keywords listed before
+    /// an identifier pattern keep winning on `if` while `iffy` lexes as one identifier.
+    pub fn longest(mut self) -> Self {
+        self.longest = true;
+        self
+    }
+}
+
+impl<'h, I, K, R, E> Parser<I, (K, <I as Stream>::Slice), E> for TokenizeParser<'h, I, K, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    K: Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<(K, <I as Stream>::Slice), E> {
+        let (index, slice) = first_match(input, &self.res, self.longest)?;
+        Ok((self.kinds[index].clone(), slice))
+    }
+}
+
+/// Creates a lexer parser that returns the kind of the token it consumed along with its text.
+///
+/// `table` lists `(kind, pattern)` entries from highest to lowest priority. Each call consumes
+/// the first entry's match at the current position, returning the entry's kind, so the parser
+/// can be run in a loop to turn the whole input into tokens. [`TokenizeParser::longest`]
+/// switches to maximal munch. Kinds are typically a `Copy` enum; they are cloned per token.
+///
+/// Like [`longest_match`], but keyed by a kind of your own instead of an index, and like
+/// [`LexerTable`], but without names. Anchor patterns with `^`; on partial input the rules
+/// of [`regex()`] apply to the chosen match.
+///
+/// # Panics
+///
+/// Panics if any of the regex patterns fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::combinator::{preceded, repeat};
+/// use winnow::prelude::*;
+/// use winnow_regex::{regex, tokenize};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq)]
+/// enum Token {
+///     Number,
+///     Op,
+///     Open,
+///     Close,
+/// }
+///
+/// fn tokens<'i>(s: &mut &'i str) -> ModalResult<Vec<(Token, &'i str)>> {
+///     let token = tokenize([
+///         (Token::Number, r"^\d+(?:\.\d+)?"),
+///         (Token::Op, r"^(?:\*\*|[-+*/])"),
+///         (Token::Open, r"^\("),
+///         (Token::Close, r"^\)"),
+///     ]);
+///     repeat(0.., preceded(regex(r"^\s*"), token)).parse_next(s)
+/// }
+///
+/// assert_eq!(
+///     tokens.parse_peek("(1.5 + 2) ** 3"),
+///     Ok((
+///         "",
+///         vec![
+///             (Token::Open, "("),
+///             (Token::Number, "1.5"),
+///             (Token::Op, "+"),
+///             (Token::Number, "2"),
+///             (Token::Close, ")"),
+///             (Token::Op, "**"),
+///             (Token::Number, "3"),
+///         ]
+///     ))
+/// );
+/// ```
+#[inline(always)]
+pub fn tokenize<'h, Input, K, Re, Error>(
+    table: impl IntoIterator<Item = (K, Re)>,
+) -> TokenizeParser<'h, Input, K, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    K: Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    let (kinds, res) = table
+        .into_iter()
+        .map(|(kind, re)| (kind, re.into_regex()))
+        .unzip();
+
+    TokenizeParser {
+        kinds,
+        res,
+        longest: false,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+pub struct RangesParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    locs: R::CaptureLocations,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> Parser<I, Vec<Option<Range<usize>>>, E> for RangesParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<Vec<Option<Range<usize>>>, E> {
+        if <I as StreamIsPartial>::is_partial_supported() {
+            captures_into::<_, _, E, true>(input, &self.re, &mut self.locs)
+        } else {
+            captures_into::<_, _, E, false>(input, &self.re, &mut self.locs)
+        }?;
+        Ok((0..self.locs.len())
+            .map(|i| self.locs.get(i).map(|(start, end)| start..end))
+            .collect())
+    }
+}
+
+/// Creates a parser that matches a regex and returns only the byte ranges of its groups.
+///
+/// Item `i` is the range of group `i` within the consumed slice, starting with group 0 for the
+/// whole match, or `None` if the group did not participate. Nothing is sliced and the output
+/// holds no borrow of the input, which suits indexing into a representation of your own.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::captures_ranges;
+///
+/// fn pair(s: &mut &str) -> ModalResult<Vec<Option<std::ops::Range<usize>>>> {
+///     captures_ranges(r"^(\w+)=(\d+)?").parse_next(s)
+/// }
+///
+/// assert_eq!(pair.parse_peek("key=;"), Ok((";", vec![Some(0..4), Some(0..3), None])));
+/// ```
+#[inline(always)]
+pub fn captures_ranges<'h, Input, Re, Error>(re: Re) -> RangesParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    let re = re.into_regex();
+
+    RangesParser {
+        locs: re.capture_locations(),
+        re,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+pub struct SizeHintParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    inner: RegexParser<'h, I, R, E>,
+    /// Fewest bytes any match of the pattern takes.
+    min_len: usize,
+}
+
+impl<'h, I, R, E> Parser<I, <I as Stream>::Slice, E> for SizeHintParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<<I as Stream>::Slice, E> {
+        let len = input.eof_offset();
+        if <I as StreamIsPartial>::is_partial_supported()
+            && input.is_partial()
+            && len < self.min_len
+        {
+            return Err(E::incomplete(input, Needed::new(self.min_len - len)));
+        }
+        self.inner.parse_next(input)
+    }
+}
+
+/// Returns the fewest UTF-8 bytes a match of `pattern` can take, or `0` if unknown.
+///
+/// The pattern is read case-insensitively: case folding can only add shorter alternatives,
+/// such as `s` for `ſ`, so the result stays a lower bound whichever way the regex was built.
+fn min_match_len(pattern: &str) -> usize {
+    regex_syntax::ParserBuilder::new()
+        .case_insensitive(true)
+        .build()
+        .parse(pattern)
+        .ok()
+        .and_then(|hir| hir.properties().minimum_len())
+        .unwrap_or(0)
+}
+
+/// Like [`regex()`], but tells a partial `str` stream how many more bytes a match needs at least.
+///
+/// The fewest bytes any match can take is worked out once from the pattern. When a partial
+/// buffer holds less than that, the parser fails with [`Needed::Size`] of the difference
+/// instead of [`Needed::Unknown`], so a reader can fetch enough input in one go. Both sides
+/// are counted in UTF-8 bytes, not chars: `^日本` needs 6 bytes, and a buffer holding `日`
+/// needs 3 more. Since the buffer of a `str` stream only ever holds whole chars, the hint is
+/// never more than a valid completion takes; it may be less, e.g. when the input arrives
+/// as multi-byte chars where the pattern also allows ASCII.
+///
+/// The estimate comes from the pattern text, as returned by [`Regex::as_str`], so builder
+/// options that change how the text is read, such as `ignore_whitespace`, are not seen, and
+/// a backend that does not keep its pattern gives no hint. Once the buffer is long enough,
+/// this behaves like [`regex()`].
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::error::{ErrMode, Needed};
+/// use winnow::prelude::*;
+/// use winnow::stream::Partial;
+/// use winnow_regex::regex_size_hint;
+///
+/// fn date<'i>(s: &mut Partial<&'i str>) -> ModalResult<&'i str> {
+///     regex_size_hint(r"^\d{4}-\d{2}-\d{2}").parse_next(s)
+/// }
+///
+/// assert_eq!(date.parse_peek(Partial::new("2024-")), Err(ErrMode::Incomplete(Needed::new(5))));
+/// ```
+#[inline(always)]
+pub fn regex_size_hint<'h, Input, Re, Error>(re: Re) -> SizeHintParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream<Slice = &'h str> + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = &'h str>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    let inner = regex(re);
+
+    SizeHintParser {
+        min_len: min_match_len(inner.re.as_str()),
+        inner,
+    }
+}
+
+pub struct KeywordCiParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    locs: R::CaptureLocations,
+    /// Position in the keyword list of the keyword behind each capture group.
+    order: Box<[usize]>,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> Parser<I, (<I as Stream>::Slice, usize), E> for KeywordCiParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<(<I as Stream>::Slice, usize), E> {
+        let slice = if <I as StreamIsPartial>::is_partial_supported() {
+            captures_into::<_, _, E, true>(input, &self.re, &mut self.locs)
+        } else {
+            captures_into::<_, _, E, false>(input, &self.re, &mut self.locs)
+        }?;
+        let group = (1..self.locs.len())
+            .find(|&i| self.locs.get(i).is_some())
+            .expect("one keyword group participates in every match");
+        Ok((slice, self.order[group - 1]))
+    }
+}
+
+/// Like [`keywords`], but matches case-insensitively and also returns which keyword matched.
+///
+/// The output pairs the matched slice, in the input's spelling, with the index of the keyword
+/// in `keywords`, so the declared spelling serves as the canonical form: with `SELECT` in the
+/// list, `select` and `sElEcT` both map to it. Keywords are escaped and tried longest first,
+/// like with [`keywords`]; keywords that only differ in case match the same text, and the
+/// first one declared wins.
+///
+/// Case-insensitivity follows the regex crate's `(?i)` flag, which uses Unicode simple case
+/// folding. No word boundary is implied.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::regex_keyword_ci;
+///
+/// const KEYWORDS: &[&str] = &["SELECT", "FROM", "WHERE"];
+///
+/// fn keyword<'i>(s: &mut &'i str) -> ModalResult<(&'i str, &'static str)> {
+///     let (text, i) = regex_keyword_ci(KEYWORDS).parse_next(s)?;
+///     Ok((text, KEYWORDS[i]))
+/// }
+///
+/// assert_eq!(keyword.parse_peek("from t"), Ok((" t", ("from", "FROM"))));
+/// ```
+#[inline(always)]
+pub fn regex_keyword_ci<'h, Input, Error>(
+    keywords: &[&str],
+) -> KeywordCiParser<'h, Input, regex::Regex, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    regex::Regex: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Error: ParserError<Input> + 'static,
+{
+    let mut order: Vec<_> = (0..keywords.len()).collect();
+    order.sort_by_key(|&i| core::cmp::Reverse(keywords[i].len()));
+    let pattern = if keywords.is_empty() {
+        r"[^\s\S]".to_owned()
+    } else {
+        let alternation: Vec<_> = order
+            .iter()
+            .map(|&i| format!("({})", regex::escape(keywords[i])))
+            .collect();
+        format!("^(?i:{})", alternation.join("|"))
+    };
+    let re = pattern.into_regex();
+
+    KeywordCiParser {
+        locs: re.capture_locations(),
+        re,
+        order: order.into(),
+        _marker: core::marker::PhantomData,
+    }
+}
+
+pub struct SeparatedParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    element: R,
+    sep: R,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> Parser<I, Vec<<I as Stream>::Slice>, E> for SeparatedParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<Vec<<I as Stream>::Slice>, E> {
+        if <I as StreamIsPartial>::is_partial_supported() {
+            separated_impl::<_, _, _, true>(input, &self.element, &self.sep)
+        } else {
+            separated_impl::<_, _, _, false>(input, &self.element, &self.sep)
+        }
+    }
+}
+
+/// Creates a parser for a list of `element` matches separated by `sep` matches, such as
+/// `a, b, c`.
+///
+/// Each element is validated against its own pattern, unlike [`split_iter`] which takes
+/// whatever lies between separators. Only the element slices are returned. Both patterns are
+/// matched at the cursor, so anchor them with `^`.
+///
+/// - If the first element does not match, the result is an empty list and nothing is
+///   consumed.
+/// - A separator that is not followed by an element, such as the trailing comma in `a, b,`,
+///   ends the list and is left unconsumed.
+/// - A separator that matches the empty string could loop forever, so it fails through
+///   [`ParserError::assert`], which panics in debug builds. Elements may match the empty
+///   string.
+///
+/// On partial input the rules of [`regex()`] apply to every match, so a match reaching the end
+/// of the buffer, or a missing one, returns `Incomplete`. The list therefore only ends on
+/// partial input once the stream is marked complete.
+///
+/// # Panics
+///
+/// Panics if either regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::separated_regex;
+///
+/// fn idents<'i>(s: &mut &'i str) -> ModalResult<Vec<&'i str>> {
+///     separated_regex(r"^[a-z]\w*", r"^\s*,\s*").parse_next(s)
+/// }
+///
+/// assert_eq!(idents.parse_peek("a, b2 ,c;"), Ok((";", vec!["a", "b2", "c"])));
+/// assert_eq!(idents.parse_peek("a, b,"), Ok((",", vec!["a", "b"])));
+/// assert_eq!(idents.parse_peek("1, 2"), Ok(("1, 2", vec![])));
+/// ```
+#[inline(always)]
+pub fn separated_regex<'h, Input, Element, Sep, Error>(
+    element: Element,
+    sep: Sep,
+) -> SeparatedParser<'h, Input, Element::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Element: RegexPattern,
+    Element::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Element::Error: Debug,
+    Sep: RegexPattern<Output = Element::Output>,
+    Sep::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    SeparatedParser {
+        element: element.into_regex(),
+        sep: sep.into_regex(),
+        _marker: core::marker::PhantomData,
+    }
+}
+
+pub struct LenParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> Parser<I, usize, E> for LenParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<usize, E> {
+        let found = match self.re.find(input.peek_finish()) {
+            Some((0, end)) => Some((end, end)),
+            _ => None,
+        };
+        if <I as StreamIsPartial>::is_partial_supported() {
+            consume_match::<_, _, _, true>(input, found)
+        } else {
+            consume_match::<_, _, _, false>(input, found)
+        }
+        .map(|(_, len)| len)
+    }
+}
+
+/// Like [`regex_void`], but returns the length of the match in bytes.
+///
+/// The match is consumed and only its length is kept, which is all that is needed to advance a
+/// position counter. Like [`regex_void`], no capture locations are computed.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::regex_len;
+///
+/// fn indent(s: &mut &str) -> ModalResult<usize> {
+///     regex_len(r"^ *").parse_next(s)
+/// }
+///
+/// assert_eq!(indent.parse_peek("    x"), Ok(("x", 4)));
+/// assert_eq!(indent.parse_peek("x"), Ok(("x", 0)));
+/// ```
+#[inline(always)]
+pub fn regex_len<'h, Input, Re, Error>(re: Re) -> LenParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    let re = re.into_regex();
+
+    LenParser {
+        re,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+pub struct ThenPeekParser<'h, I, R, E = ErrMode<ContextError>>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> Parser<I, (<I as Stream>::Slice, Option<<I as Stream>::Token>), E>
+    for ThenPeekParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(
+        &mut self,
+        input: &mut I,
+    ) -> Result<(<I as Stream>::Slice, Option<<I as Stream>::Token>), E> {
+        let found = match self.re.find(input.peek_finish()) {
+            Some((0, end)) => Some((end, ())),
+            _ => None,
+        };
+        let (slice, _) = if <I as StreamIsPartial>::is_partial_supported() {
+            consume_match::<_, _, _, true>(input, found)
+        } else {
+            consume_match::<_, _, _, false>(input, found)
+        }?;
+        Ok((slice, input.peek_token()))
+    }
+}
+
+/// Like [`regex()`], but also peeks at the token right after the match.
+///
+/// The second element is the next `char` of a `&str` stream or the next byte of a `&[u8]`
+/// stream, or `None` at the end of the input. It is not consumed, so a lexer can decide what
+/// to do next without a separate `peek` parser.
+///
+/// On partial input a match that reaches the end of the buffer already returns `Incomplete`,
+/// as for [`regex()`], so a successful match always has the following token available.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::regex_then_peek;
+///
+/// fn ident<'i>(s: &mut &'i str) -> ModalResult<(&'i str, Option<char>)> {
+///     regex_then_peek(r"^\w+").parse_next(s)
+/// }
+///
+/// assert_eq!(ident.parse_peek("call(x)"), Ok(("(x)", ("call", Some('(')))));
+/// assert_eq!(ident.parse_peek("end"), Ok(("", ("end", None))));
+/// ```
+#[inline(always)]
+pub fn regex_then_peek<'h, Input, Re, Error>(re: Re) -> ThenPeekParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    let re = re.into_regex();
+
+    ThenPeekParser {
+        re,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+pub struct SplitParser<'h, I, R, E>
+where
+    I: Stream,
+    R: Regex,
+    E: ParserError<I>,
+{
+    re: R,
+    last: bool,
+    _marker: core::marker::PhantomData<(&'h (), I, E)>,
+}
+
+impl<'h, I, R, E> Parser<I, <I as Stream>::Slice, E> for SplitParser<'h, I, R, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    fn parse_next(&mut self, input: &mut I) -> Result<<I as Stream>::Slice, E> {
+        if <I as StreamIsPartial>::is_partial_supported() {
+            split_impl::<_, _, _, true>(input, &self.re, self.last)
+        } else {
+            split_impl::<_, _, _, false>(input, &self.re, self.last)
+        }
+    }
+}
+
+/// Creates a parser that returns one field of input split by a separator regex.
+///
+/// Each call searches the remaining input for the next match of `re` (which is **not**
+/// anchored), returns the text before it, and consumes both the field and the separator.
+/// This mirrors [`regex::Regex::split`] one field at a time, so it can be driven by
+/// [`winnow::combinator::repeat`] without buffering all fields up front.
+///
+/// Only fields that are followed by a separator are produced. Once no separator remains the
+/// parser fails, which ends a `repeat`, and the final field is left for [`split_last`]. Chaining
+/// the two yields exactly the fields of `Regex::split`, including the empty field after a
+/// trailing separator.
+///
+/// On partial input, a missing separator or one that ends at the end of the buffer returns
+/// `Incomplete`, since more data may complete it.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+///
+/// A separator that matches the empty string at the start of a field would make no progress,
+/// so it fails through [`ParserError::assert`], which panics in debug builds. In release
+/// builds the parser fails instead; with `ErrMode` the failure is a cut.
+///
+/// # Example
+///
+/// ```
+/// use winnow::combinator::repeat;
+/// use winnow::prelude::*;
+/// use winnow_regex::{split_iter, split_last};
+///
+/// fn fields<'i>(s: &mut &'i str) -> ModalResult<(Vec<&'i str>, &'i str)> {
+///     (repeat(0.., split_iter(r",\s*")), split_last(r",\s*")).parse_next(s)
+/// }
+///
+/// assert_eq!(fields.parse_peek("a, b,c"), Ok(("", (vec!["a", "b"], "c"))));
+/// assert_eq!(fields.parse_peek("a,"), Ok(("", (vec!["a"], ""))));
+/// ```
+#[inline(always)]
+pub fn split_iter<'h, Input, Re, Error>(re: Re) -> SplitParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    let re = re.into_regex();
+
+    SplitParser {
+        re,
+        last: false,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+/// Creates a parser that consumes the final field left over by [`split_iter`].
+///
+/// It succeeds with the rest of the input only if `re` no longer matches anywhere in it, and
+/// fails otherwise. On partial input it always returns `Incomplete`, because the final field
+/// extends to the end of the stream.
+///
+/// # Panics
+///
+/// Panics if the regex pattern fails to compile.
+#[inline(always)]
+pub fn split_last<'h, Input, Re, Error>(re: Re) -> SplitParser<'h, Input, Re::Output, Error>
+where
+    Input: StreamIsPartial + Stream + Offset + Clone,
+    Re: RegexPattern,
+    Re::Output: Regex<Haystack<'h> = <Input as Stream>::Slice>,
+    Re::Error: Debug,
+    Error: ParserError<Input> + 'static,
+{
+    let re = re.into_regex();
+
+    SplitParser {
+        re,
+        last: true,
+        _marker: core::marker::PhantomData,
+    }
+}
+
+fn split_impl<'h, I, Re, E, const PARTIAL: bool>(
+    input: &mut I,
+    re: &Re,
+    last: bool,
+) -> Result<<I as Stream>::Slice, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    Re: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    let hay = input.peek_finish();
+    let mut locs = re.capture_locations();
+
+    match re.captures_read(&mut locs, hay) {
+        Some((0, 0)) if !last => Err(E::assert(
+            input,
+            "separator must not match the empty string",
+        )),
+        Some((_, end)) if PARTIAL && input.is_partial() && input.eof_offset() == end => {
+            Err(E::incomplete(input, Needed::Unknown))
+        }
+        Some((start, end)) if !last => {
+            let field = input.next_slice(start);
+            input.next_slice(end - start);
+            Ok(field)
+        }
+        _ if PARTIAL && input.is_partial() => Err(E::incomplete(input, Needed::Unknown)),
+        None if last => Ok(input.finish()),
+        _ => Err(ParserError::from_input(input)),
+    }
+}
+
+fn separated_impl<'h, I, Re, E, const PARTIAL: bool>(
+    input: &mut I,
+    element: &Re,
+    sep: &Re,
+) -> Result<Vec<<I as Stream>::Slice>, E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    Re: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    let mut elements = Vec::new();
+    match captures_impl::<_, _, E, PARTIAL>(input, element) {
+        Ok((slice, _)) => elements.push(slice),
+        Err(e) if e.is_backtrack() => return Ok(elements),
+        Err(e) => return Err(e),
+    }
+    loop {
+        let before_sep = input.checkpoint();
+        match captures_impl::<_, _, E, PARTIAL>(input, sep) {
+            Ok(_) if input.offset_from(&before_sep) == 0 => {
+                return Err(E::assert(
+                    input,
+                    "separator must not match the empty string",
+                ));
+            }
+            Ok(_) => {}
+            Err(e) if e.is_backtrack() => return Ok(elements),
+            Err(e) => return Err(e),
+        }
+        match captures_impl::<_, _, E, PARTIAL>(input, element) {
+            Ok((slice, _)) => elements.push(slice),
+            Err(e) if e.is_backtrack() => {
+                input.reset(&before_sep);
+                return Ok(elements);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+fn captures_impl<'h, I, Re, E, const PARTIAL: bool>(
+    input: &mut I,
+    re: &Re,
+) -> Result<(<I as Stream>::Slice, Re::CaptureLocations), E>
+where
+    I: Stream + StreamIsPartial + Offset + Clone,
+    Re: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    let found = find_at_start(input, re);
+    consume_match::<_, _, _, PARTIAL>(input, found)
+}
+
+/// Runs `re` over the remaining input, returning the end of the match and its capture locations
+/// if it matched at offset 0.
+fn find_at_start<'h, I, Re>(input: &I, re: &Re) -> Option<(usize, Re::CaptureLocations)>
+where
+    I: Stream + Clone,
+    Re: Regex<Haystack<'h> = <I as Stream>::Slice>,
+{
+    let mut locs = re.capture_locations();
+
+    match re.captures_read(&mut locs, input.peek_finish()) {
+        Some((0, end)) => Some((end, locs)),
+        _ => None,
+    }
+}
+
+/// Like [`captures_impl`], but fills the caller's capture locations instead of allocating new
+/// ones, returning only the matched slice.
+///
+/// Parsers keep their capture locations in a field and pass them here on every call, so a
+/// pattern with many groups does not allocate per match.
+fn captures_into<'h, I, Re, E, const PARTIAL: bool>(
+    input: &mut I,
+    re: &Re,
+    locs: &mut Re::CaptureLocations,
+) -> Result<<I as Stream>::Slice, E>
+where
+    I: Stream + StreamIsPartial + Clone,
+    Re: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    let found = find_into(input, re, locs);
+    consume_match::<_, _, _, PARTIAL>(input, found).map(|(slice, _)| slice)
+}
+
+/// Like [`find_at_start`], but fills the caller's capture locations.
+fn find_into<'h, I, Re>(input: &I, re: &Re, locs: &mut Re::CaptureLocations) -> Option<(usize, ())>
+where
+    I: Stream + Clone,
+    Re: Regex<Haystack<'h> = <I as Stream>::Slice>,
+{
+    match re.captures_read(locs, input.peek_finish()) {
+        Some((0, end)) => Some((end, ())),
+        _ => None,
+    }
+}
+
+/// Returns the `start..end` span of the input at `checkpoint` as a slice, leaving `input` where
+/// it was.
+fn slice_at<I: Stream>(
+    input: &mut I,
+    checkpoint: &<I as Stream>::Checkpoint,
+    (start, end): (usize, usize),
+) -> <I as Stream>::Slice {
+    let current = input.checkpoint();
+    input.reset(checkpoint);
+    input.next_slice(start);
+    let slice = input.next_slice(end - start);
+    input.reset(&current);
+    slice
+}
+
+/// Tries each of `res` at the start of the remaining input and consumes the match of the first
+/// one that matches, or with `longest` the longest match, ties going to the earlier regex.
+/// Returns the index of the winning regex along with the matched slice.
+fn first_match<'h, I, R, E>(
+    input: &mut I,
+    res: &[R],
+    longest: bool,
+) -> Result<(usize, <I as Stream>::Slice), E>
+where
+    I: Stream + StreamIsPartial + Clone,
+    R: Regex<Haystack<'h> = <I as Stream>::Slice>,
+    E: ParserError<I>,
+{
+    let mut found: Option<(usize, usize)> = None;
+    for (index, re) in res.iter().enumerate() {
+        if let Some((0, end)) = re.find(input.peek_finish()) {
+            // Strictly longer only, so ties go to the earlier regex.
+            if found.is_none_or(|(best, _)| end > best) {
+                found = Some((end, index));
+            }
+            if !longest {
+                break;
+            }
+        }
+    }
+
+    let (slice, index) = if <I as StreamIsPartial>::is_partial_supported() {
+        consume_match::<_, _, E, true>(input, found)
+    } else {
+        consume_match::<_, _, E, false>(input, found)
+    }?;
+    Ok((index, slice))
+}
+
+/// Consumes a match found by [`find_at_start`], applying the partial-input rules: a match that
+/// reaches the end of a partial buffer, or no match at all, may change with more data.
+fn consume_match<I, L, E, const PARTIAL: bool>(
+    input: &mut I,
+    found: Option<(usize, L)>,
+) -> Result<(<I as Stream>::Slice, L), E>
+where
+    I: Stream + StreamIsPartial,
+    E: ParserError<I>,
+{
+    match found {
+        Some((end, _)) if PARTIAL && input.is_partial() && input.eof_offset() == end => {
+            Err(E::incomplete(input, Needed::Unknown))
+        }
+        Some((end, locs)) => Ok((input.next_slice(end), locs)),
+        None if PARTIAL && input.is_partial() => Err(E::incomplete(input, Needed::Unknown)),
+        None => Err(ParserError::from_input(input)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use winnow::error::{ContextError, EmptyError, ErrMode};
+    use winnow::prelude::*;
+
+    #[test]
+    fn regex_parser() {
+        let mut p: RegexParser<&str, regex::Regex, EmptyError> = RegexParser {
+            re: regex::Regex::new(r"^\d+").unwrap(),
+            _marker: core::marker::PhantomData,
+        };
+        assert_eq!(p.parse_peek("42abc"), Ok(("abc", "42")));
+    }
+
+    /// An error type implementing nothing beyond [`ParserError`].
+    #[derive(Debug, PartialEq)]
+    struct PlainError;
+
+    impl<I: Stream> ParserError<I> for PlainError {
+        type Inner = Self;
+
+        fn from_input(_input: &I) -> Self {
+            PlainError
+        }
+
+        fn into_inner(self) -> Result<Self, Self> {
+            Ok(self)
+        }
+    }
+
+    #[test]
+    fn regex_plain_error() {
+        let mut p = regex::<_, _, PlainError>(r"^\d+");
+        assert_eq!(p.parse_peek("42abc"), Ok(("abc", "42")));
+        assert_eq!(p.parse_peek("abc"), Err(PlainError));
+    }
+
+    #[test]
+    fn ok_with_literal_pattern() {
+        fn digits<'i>(s: &mut &'i str) -> ModalResult<&'i str> {
+            regex(r"^\d+").parse_next(s)
+        }
+        assert_eq!(digits.parse_peek("42xyz"), Ok(("xyz", "42")));
+    }
+
+    #[test]
+    fn hoisted_captures_parser() {
+        let mut parser = captures::<_, _, EmptyError>(r"^(\d+)([a-z]),?");
+        let mut input = "1a,22b,333c";
+        for (n, c) in [("1", "a"), ("22", "b"), ("333", "c")] {
+            let caps = parser.parse_next(&mut input).unwrap();
+            assert_eq!((&caps[1], &caps[2]), (n, c));
+        }
+        assert!(parser.parse_next(&mut input).is_err());
+
+        let mut cloned = parser.clone();
+        assert_eq!(&cloned.parse_next(&mut "4d").unwrap()[1], "4");
+    }
+
+    #[test]
+    fn gaps_skip_nested_groups() {
+        let caps = captures::<_, _, EmptyError>(r"^<((\w)\w*)>=(\w+)(;)")
+            .parse_next(&mut "<key>=value;")
+            .unwrap();
+        assert_eq!(caps.gaps(), [">=", ""]);
+    }
+
+    #[test]
+    fn rebuild() {
+        let caps = captures::<_, _, EmptyError>(r"^(\d+)x(\d+)")
+            .parse_next(&mut "12x34;")
+            .unwrap();
+        assert_eq!(caps.rebuild(&[(2, "56")]), "12x56");
+        assert_eq!(caps.rebuild(&[]), "12x34");
+        assert_eq!(caps.rebuild(&[(0, "m"), (1, "a")]), "m");
+        assert_eq!(caps.rebuild(&[(2, "5"), (2, "6"), (9, "?")]), "12x5");
+
+        let caps = captures::<_, _, EmptyError>(r"^<((\w)\w*)>(?:=(\w+))?")
+            .parse_next(&mut "<key>")
+            .unwrap();
+        assert_eq!(caps.rebuild(&[(2, "x"), (3, "v")]), "<xey>");
+    }
+
+    #[test]
+    fn ctx_infers_error() {
+        let mut s = "12x34;";
+        let caps = captures_ctx(r"^(\d+)x(\d+)").parse_next(&mut s).unwrap();
+        assert_eq!((&caps[1], &caps[2]), ("12", "34"));
+        assert_eq!(regex_ctx(r"^;").parse_next(&mut s), Ok(";"));
+        assert!(regex_ctx(r"^x").parse_next(&mut s).is_err());
+    }
+
+    #[test]
+    fn named_iter_keeps_pattern_order() {
+        let mut s = &b"b=2,a=1"[..];
+        let caps = crate::bytes::captures::<_, _, EmptyError>(r"^(?<z>\w)=(\d),(?<a>\w)=(?<m>x)?")
+            .parse_next(&mut s)
+            .unwrap();
+        let named: Vec<_> = caps.named_iter().collect();
+        assert_eq!(
+            named,
+            [("z", Some(&b"b"[..])), ("a", Some(&b"a"[..])), ("m", None)]
+        );
+        assert_eq!(caps.len(), 5);
+        assert_eq!(caps.get(2), Some(&b"2"[..]));
+        assert_eq!(caps.range(2), Some(2..3));
+        assert_eq!(caps.range(4), None);
+    }
+
+    #[test]
+    fn unicode_partial() {
+        let mut s = "あいう123";
+        let re = regex::<_, _, EmptyError>(r"^[ぁ-ん]+")
             .parse_next(&mut s)
             .unwrap();
-        assert_eq!(re, "あいう");
+        assert_eq!(re, "あいう");
+    }
+
+    #[test]
+    fn partial() {
+        use winnow::stream::Partial;
+        fn partial<'i>(i: &mut Partial<&'i [u8]>) -> ModalResult<&'i [u8], ContextError> {
+            regex(regex::bytes::Regex::new(r"^\d+").unwrap()).parse_next(i)
+        }
+        assert_eq!(
+            partial.parse_peek(Partial::new(&b"123abc"[..])),
+            Ok((Partial::new(&b"abc"[..]), &b"123"[..]))
+        );
+        assert_eq!(
+            partial.parse_peek(Partial::new(&b"123"[..])),
+            Err(ErrMode::Incomplete(Needed::Unknown))
+        );
+    }
+
+    #[test]
+    fn max_len_partial() {
+        use winnow::stream::Partial;
+        fn token<'i>(i: &mut Partial<&'i [u8]>) -> ModalResult<&'i [u8]> {
+            regex_max_len(regex::bytes::Regex::new(r"^.+").unwrap(), 3).parse_next(i)
+        }
+        assert_eq!(
+            token.parse_peek(Partial::new(&b"ab"[..])),
+            Err(ErrMode::Incomplete(Needed::Unknown))
+        );
+        assert!(matches!(
+            token.parse_peek(Partial::new(&b"abcd"[..])),
+            Err(ErrMode::Backtrack(_))
+        ));
+        assert_eq!(
+            token.parse_peek(Partial::new(&b"abc\n"[..])),
+            Ok((Partial::new(&b"\n"[..]), &b"abc"[..]))
+        );
+    }
+
+    #[test]
+    fn split_at_partial() {
+        use winnow::stream::Partial;
+        fn key<'i>(i: &mut Partial<&'i str>) -> ModalResult<(&'i str, &'i str)> {
+            split_at_regex(r"^\w+=").parse_next(i)
+        }
+        assert_eq!(
+            key.parse_peek(Partial::new("a=1")),
+            Ok((Partial::new("1"), ("a=", "1")))
+        );
+        assert_eq!(
+            key.parse_peek(Partial::new("a=")),
+            Err(ErrMode::Incomplete(Needed::Unknown))
+        );
+    }
+
+    #[test]
+    fn repeated_group_skips_missing() {
+        use winnow::stream::Partial;
+        fn items<'i>(i: &mut &'i str) -> ModalResult<Vec<&'i str>> {
+            repeated_group(r"^(?:(\d+)|x);", 1).parse_next(i)
+        }
+        assert_eq!(items.parse_peek("1;x;22;."), Ok((".", vec!["1", "22"])));
+        assert_eq!(
+            repeated_group::<_, _, EmptyError>(r"^\d*", 0).parse_peek("12a"),
+            Ok(("a", vec!["12"]))
+        );
+        assert_eq!(
+            repeated_group::<_, _, ErrMode<ContextError>>(r"^\d;", 0)
+                .parse_peek(Partial::new("1;2")),
+            Err(ErrMode::Incomplete(Needed::Unknown))
+        );
+    }
+
+    #[test]
+    fn is_regex_does_not_consume() {
+        use winnow::stream::Partial;
+        let mut p =
+            is_regex::<_, _, ErrMode<ContextError>>(regex::bytes::Regex::new("^ab").unwrap());
+        assert_eq!(
+            p.parse_peek(Partial::new(&b"abc"[..])),
+            Ok((Partial::new(&b"abc"[..]), ()))
+        );
+        assert_eq!(
+            p.parse_peek(Partial::new(&b"a"[..])),
+            Err(ErrMode::Incomplete(Needed::Unknown))
+        );
+    }
+
+    #[test]
+    fn is_regex_unanchored_matches_at_cursor() {
+        let mut p = is_regex::<_, _, EmptyError>(r"\d");
+        assert_eq!(p.parse_peek("1abc"), Ok(("1abc", ())));
+        assert!(p.parse_peek("abc1").is_err());
+    }
+
+    #[test]
+    fn keywords_longest_first() {
+        let mut p = keywords::<_, EmptyError>(&["el", "else", "e"]);
+        assert_eq!(p.parse_peek("else"), Ok(("", "else")));
+        assert_eq!(p.parse_peek("elk"), Ok(("k", "el")));
+        assert_eq!(p.parse_peek("eel"), Ok(("el", "e")));
+        assert!(keywords::<_, EmptyError>(&[]).parse_peek("").is_err());
+    }
+
+    #[test]
+    fn regex_ws_partial() {
+        use winnow::stream::Partial;
+        fn token<'i>(i: &mut Partial<&'i str>) -> ModalResult<&'i str> {
+            regex_ws(r"^[a-z]+").parse_next(i)
+        }
+        assert_eq!(
+            token.parse_peek(Partial::new("ab \tc")),
+            Ok((Partial::new("c"), "ab"))
+        );
+        assert_eq!(
+            token.parse_peek(Partial::new("ab;")),
+            Ok((Partial::new(";"), "ab"))
+        );
+        assert_eq!(
+            token.parse_peek(Partial::new("ab  ")),
+            Err(ErrMode::Incomplete(Needed::Unknown))
+        );
+    }
+
+    #[test]
+    fn ws_regex_partial() {
+        use winnow::stream::Partial;
+        fn token<'i>(i: &mut Partial<&'i str>) -> ModalResult<&'i str> {
+            ws_regex(r"^[a-z]+").parse_next(i)
+        }
+        assert_eq!(
+            token.parse_peek(Partial::new(" \tab;")),
+            Ok((Partial::new(";"), "ab"))
+        );
+        assert_eq!(
+            token.parse_peek(Partial::new("  ")),
+            Err(ErrMode::Incomplete(Needed::Unknown))
+        );
+        let mut input = Partial::new(" ;");
+        assert!(token.parse_next(&mut input).is_err());
+        assert_eq!(input, Partial::new(" ;"));
+    }
+
+    #[test]
+    fn regex_window_exhausted() {
+        use winnow::stream::Partial;
+        let input = Partial::new("ab\u{e9}cdef");
+        let word = r"^[a-z\u{e9}]+";
+        let mut incomplete =
+            regex_window::<_, _, ErrMode<ContextError>>(word, 3, WindowExhausted::Incomplete);
+        assert_eq!(
+            incomplete.parse_peek(input),
+            Err(ErrMode::Incomplete(Needed::Unknown))
+        );
+        let mut fail = regex_window::<_, _, ErrMode<ContextError>>(word, 3, WindowExhausted::Fail);
+        assert!(matches!(fail.parse_peek(input), Err(ErrMode::Backtrack(_))));
+        // The window is cut before the two-byte `\u{e9}`, so only `ab` is searched.
+        let mut a = regex_window::<_, _, ErrMode<ContextError>>("^a", 3, WindowExhausted::Fail);
+        assert_eq!(a.parse_peek(input), Ok((Partial::new("b\u{e9}cdef"), "a")));
+    }
+
+    #[test]
+    fn prefiltered_partial() {
+        use winnow::stream::Partial;
+        let mut p = regex_prefiltered::<_, _, ErrMode<ContextError>>(r"^-?\d+", b"-0123456789");
+        assert_eq!(
+            p.parse_peek(Partial::new("-12;")),
+            Ok((Partial::new(";"), "-12"))
+        );
+        assert_eq!(
+            p.parse_peek(Partial::new("")),
+            Err(ErrMode::Incomplete(Needed::Unknown))
+        );
+        assert!(matches!(
+            p.parse_peek(Partial::new("x1")),
+            Err(ErrMode::Backtrack(_))
+        ));
+    }
+
+    #[test]
+    fn regex_void_partial() {
+        use winnow::stream::Partial;
+        let mut p = regex_void::<_, _, ErrMode<ContextError>>(r"^,\s*");
+        assert_eq!(
+            p.parse_peek(Partial::new(", x")),
+            Ok((Partial::new("x"), ()))
+        );
+        assert_eq!(
+            p.parse_peek(Partial::new(", ")),
+            Err(ErrMode::Incomplete(Needed::Unknown))
+        );
+        let mut complete = Partial::new("x, ");
+        let _ = complete.complete();
+        assert!(matches!(p.parse_peek(complete), Err(ErrMode::Backtrack(_))));
+    }
+
+    #[test]
+    fn regex_present_never_fails() {
+        use winnow::stream::Partial;
+        let mut p = regex_present::<_, _, ErrMode<ContextError>>(r"^\d");
+        assert_eq!(p.parse_peek("1a"), Ok(("1a", true)));
+        assert_eq!(p.parse_peek("a1"), Ok(("a1", false)));
+        assert_eq!(p.parse_peek(""), Ok(("", false)));
+        let mut unanchored = regex_present::<_, _, ErrMode<ContextError>>(r"\d");
+        assert_eq!(unanchored.parse_peek("1a"), Ok(("1a", true)));
+        assert_eq!(unanchored.parse_peek("abc1"), Ok(("abc1", false)));
+        let mut partial = regex_present::<_, _, ErrMode<ContextError>>(r"^\d");
+        assert_eq!(
+            partial.parse_peek(Partial::new("")),
+            Ok((Partial::new(""), false))
+        );
+    }
+
+    #[test]
+    fn regex_from_builder_reports_build_error() {
+        let mut builder = regex::RegexBuilder::new(r"^\d{3}");
+        builder.size_limit(10);
+        assert!(matches!(
+            regex_from_builder::<&str, ErrMode<ContextError>>(&builder),
+            Err(Error::Regex(regex::Error::CompiledTooBig(10)))
+        ));
+    }
+
+    #[test]
+    fn many_groups() {
+        const GROUPS: usize = 300;
+        let pattern: String = (0..GROUPS)
+            .map(|i| format!("(?<g{i}>{})", i % 10))
+            .collect();
+        let input: String = (0..GROUPS)
+            .map(|i| char::from(b'0' + (i % 10) as u8))
+            .collect();
+        let caps = captures::<_, _, ErrMode<ContextError>>(format!("^{pattern}"))
+            .parse_next(&mut input.as_str())
+            .unwrap();
+
+        assert_eq!(caps.len(), GROUPS + 1);
+        assert_eq!(caps.get(0), Some(input.as_str()));
+        for i in 0..GROUPS {
+            assert_eq!(&caps[i + 1], &input[i..i + 1]);
+            assert_eq!(caps.range(i + 1), Some(i..i + 1));
+        }
+        assert_eq!(caps.get(GROUPS + 1), None);
+        assert_eq!(caps.iter().len(), GROUPS + 1);
+        assert!(caps.iter().all(|group| group.is_some()));
+        assert_eq!(caps.named_iter().count(), GROUPS);
+        assert_eq!(caps.named_iter().last(), Some(("g299", Some("9"))));
+    }
+
+    #[test]
+    fn preceded_by_resets_on_error() {
+        use winnow::stream::Partial;
+        let mut p = preceded_by::<_, _, _, ErrMode<ContextError>>(r"^#", r"^[0-9a-f]+");
+        let mut input = "#xyz";
+        assert!(p.parse_next(&mut input).is_err());
+        assert_eq!(input, "#xyz");
+
+        let mut p = preceded_by::<_, _, _, ErrMode<ContextError>>(r"^#", r"^[0-9a-f]+");
+        assert_eq!(
+            p.parse_peek(Partial::new("#ff;")),
+            Ok((Partial::new(";"), "ff"))
+        );
+        assert_eq!(
+            p.parse_peek(Partial::new("#ff")),
+            Err(ErrMode::Incomplete(Needed::Unknown))
+        );
+    }
+
+    #[test]
+    fn captures_detailed_bytes() {
+        let mut input = &b"k:v rest"[..];
+        let groups = captures_detailed::<_, _, ErrMode<ContextError>>(
+            regex::bytes::Regex::new(r"^(\w):(\w)(?:=(\w))?").unwrap(),
+        )
+        .parse_next(&mut input)
+        .unwrap();
+        assert_eq!(
+            groups,
+            [
+                Some((0..3, &b"k:v"[..])),
+                Some((0..1, &b"k"[..])),
+                Some((2..3, &b"v"[..])),
+                None,
+            ]
+        );
+        assert_eq!(input, b" rest");
+    }
+
+    #[test]
+    fn recoverable() {
+        use winnow::RecoverableParser;
+        use winnow::combinator::{repeat, terminated};
+        use winnow::stream::Recoverable;
+        type Input<'i> = Recoverable<&'i str, ContextError>;
+
+        fn number<'i>(s: &mut Input<'i>) -> Result<Option<&'i str>, ContextError> {
+            terminated(regex(r"^\d+"), regex(r"^ *"))
+                .resume_after(regex(r"^\S+ *").void())
+                .parse_next(s)
+        }
+        fn numbers<'i>(s: &mut Input<'i>) -> Result<Vec<Option<&'i str>>, ContextError> {
+            repeat(0.., number).parse_next(s)
+        }
+
+        let (_, numbers, errors) = numbers.recoverable_parse("12 x3 45");
+        assert_eq!(numbers, Some(vec![Some("12"), None, Some("45")]));
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn map_group_errors() {
+        fn hex(s: &mut &str) -> ModalResult<u8> {
+            map_group(r"^0x([0-9a-z]+)?", 1, |digits: &str| {
+                u8::from_str_radix(digits, 16)
+            })
+            .parse_next(s)
+        }
+        assert_eq!(hex.parse_peek("0xff rest"), Ok((" rest", 0xff)));
+
+        let mut input = "0xzz";
+        let err = hex.parse_next(&mut input).unwrap_err();
+        assert_eq!(input, "0xzz");
+        assert!(err.into_inner().unwrap().cause().is_some());
+
+        let mut input = "0x";
+        assert!(hex.parse_next(&mut input).is_err());
+        assert_eq!(input, "0x");
+    }
+
+    #[test]
+    fn find_iter_matches() {
+        let words: Vec<_> = find_iter(r"\b\w", "ab cd")
+            .map(|m| (m.span(), m.as_match().to_owned()))
+            .collect();
+        assert_eq!(words, [(0..1, "a".to_owned()), (3..4, "c".to_owned())]);
+
+        let empty: Vec<_> = find_iter(r"a*", "baaé").map(|m| m.span()).collect();
+        assert_eq!(empty, [0..0, 1..3, 5..5]);
+
+        let re = regex::bytes::Regex::new(r"(?-u)\xff(\w)").unwrap();
+        let bytes: Vec<_> = find_iter(re, &b"\xffa-\xffb"[..])
+            .map(|m| (m.absolute_range(1), m.range(1), m[1].to_vec()))
+            .collect();
+        assert_eq!(
+            bytes,
+            [
+                (Some(1..2), Some(1..2), b"a".to_vec()),
+                (Some(4..5), Some(1..2), b"b".to_vec()),
+            ]
+        );
+    }
+
+    #[test]
+    fn name_range_bytes() {
+        let caps = bytes::captures::<_, _, ErrMode<ContextError>>(r"^(?<k>\w+)(?:=(?<v>\w+))?")
+            .parse_next(&mut &b"key;"[..])
+            .unwrap();
+        assert_eq!(caps.name_range("k"), Some(0..3));
+        assert_eq!(caps.name_range("v"), None);
+        assert_eq!(caps.name_range("missing"), None);
+    }
+
+    #[test]
+    fn regex_transformed_bytes() {
+        use winnow::stream::Partial;
+        let mut p = regex_transformed::<_, _, _, ErrMode<ContextError>>(
+            regex::bytes::Regex::new(r"^GET ").unwrap(),
+            <[u8]>::to_ascii_uppercase,
+        );
+        assert_eq!(
+            p.parse_peek(Partial::new(&b"get /"[..])),
+            Ok((Partial::new(&b"/"[..]), &b"get "[..]))
+        );
+        assert_eq!(
+            p.parse_peek(Partial::new(&b"get"[..])),
+            Err(ErrMode::Incomplete(Needed::Unknown))
+        );
+    }
+
+    #[test]
+    fn regex_transformed_length_change() {
+        let mut p = regex_transformed::<_, _, _, ContextError>(r"^i", str::to_lowercase);
+        assert_eq!(p.parse_peek("Ia"), Ok(("a", "I")));
+        // `İ` lowercases to `i` and a combining dot, one byte longer.
+        let err = p.parse_peek("İa").unwrap_err();
+        let cause = err.cause().unwrap();
+        assert_eq!(cause.downcast_ref(), Some(&LengthChangedError));
+    }
+
+    #[test]
+    fn regex_eof_flag_partial() {
+        use winnow::stream::Partial;
+        let mut p = regex_eof_flag::<_, _, ErrMode<ContextError>>(r"^\d*");
+        assert_eq!(
+            p.parse_peek(Partial::new("12;")),
+            Ok((Partial::new(";"), ("12", false)))
+        );
+        assert_eq!(
+            p.parse_peek(Partial::new("12")),
+            Err(ErrMode::Incomplete(Needed::Unknown))
+        );
+        let mut p = regex_eof_flag::<_, _, ErrMode<ContextError>>(r"^\d*");
+        assert_eq!(p.parse_peek(""), Ok(("", ("", true))));
+    }
+
+    #[test]
+    fn captures_owned_bytes() {
+        let caps = {
+            let frame = b"\x01id=7;".to_vec();
+            bytes::captures::<_, _, ErrMode<ContextError>>(r"^\x01(?<key>\w+)=(?<value>\d+)")
+                .parse_next(&mut frame.as_slice())
+                .unwrap()
+                .to_owned_captures()
+        };
+        let copy = caps.clone();
+        assert_eq!(copy.as_match(), b"\x01id=7");
+        assert_eq!(copy.len(), 3);
+        assert_eq!(
+            copy.named_iter().collect::<Vec<_>>(),
+            [("key", Some(&b"id"[..])), ("value", Some(&b"7"[..]))]
+        );
+        assert_eq!(caps.into_match(), b"\x01id=7");
+    }
+
+    #[test]
+    fn separated_regex_partial() {
+        use winnow::stream::Partial;
+        let mut p = separated_regex::<_, _, _, ErrMode<ContextError>>(r"^\d+", r"^,");
+        assert_eq!(p.parse_peek("1,22,x"), Ok((",x", vec!["1", "22"])));
+        let mut p = separated_regex::<_, _, _, ErrMode<ContextError>>(r"^\d+", r"^,");
+        assert_eq!(
+            p.parse_peek(Partial::new("1,22,")),
+            Err(ErrMode::Incomplete(Needed::Unknown))
+        );
+        let mut p = separated_regex::<_, _, _, ErrMode<ContextError>>(r"^\d*", r"^;");
+        assert_eq!(p.parse_peek(";;1."), Ok((".", vec!["", "", "1"])));
+    }
+
+    #[test]
+    #[should_panic = "separator must not match the empty string"]
+    #[cfg(debug_assertions)]
+    fn separated_regex_empty_separator() {
+        let mut p = separated_regex::<_, _, _, ErrMode<ContextError>>(r"^\d", r"^,?");
+        let _ = p.parse_peek("12");
+    }
+
+    #[test]
+    fn regex_len_bytes() {
+        use winnow::stream::Partial;
+        let mut p = regex_len::<_, _, ErrMode<ContextError>>(
+            regex::bytes::Regex::new(r"^(?-u)[\x80-\xff]+").unwrap(),
+        );
+        assert_eq!(
+            p.parse_peek(Partial::new(&b"\xff\xfe\x00"[..])),
+            Ok((Partial::new(&b"\x00"[..]), 2))
+        );
+        assert_eq!(
+            p.parse_peek(Partial::new(&b"\xff"[..])),
+            Err(ErrMode::Incomplete(Needed::Unknown))
+        );
+    }
+
+    #[test]
+    fn locating_bytes() {
+        use winnow::stream::{LocatingSlice, Location};
+        let mut input = LocatingSlice::new(&b"ab12cd"[..]);
+        bytes::regex::<_, _, ErrMode<ContextError>>(r"^[a-z]+")
+            .parse_next(&mut input)
+            .unwrap();
+        let caps = bytes::captures::<_, _, ErrMode<ContextError>>(r"^(\d)(\d)")
+            .parse_next(&mut input)
+            .unwrap();
+        assert_eq!(&caps[2], b"2");
+        assert_eq!(input.current_token_start(), 4);
+    }
+
+    #[test]
+    fn regex_then_peek_bytes() {
+        use winnow::stream::Partial;
+        let mut p = regex_then_peek::<_, _, ErrMode<ContextError>>(
+            regex::bytes::Regex::new(r"^\d+").unwrap(),
+        );
+        assert_eq!(
+            p.parse_peek(Partial::new(&b"12\r\n"[..])),
+            Ok((Partial::new(&b"\r\n"[..]), (&b"12"[..], Some(b'\r'))))
+        );
+        assert_eq!(
+            p.parse_peek(Partial::new(&b"12"[..])),
+            Err(ErrMode::Incomplete(Needed::Unknown))
+        );
+    }
+
+    #[test]
+    fn debug_dump_bytes() {
+        let caps = bytes::captures::<_, _, ErrMode<ContextError>>(r"^(?<tag>\w)(\d)")
+            .parse_next(&mut &b"a1"[..])
+            .unwrap();
+        assert_eq!(caps.debug_dump(), "#0: [97, 49]\n#1 tag: [97]\n#2: [49]\n");
+    }
+
+    #[test]
+    fn regex_diagnostic_partial() {
+        use winnow::stream::Partial;
+        let mut p = regex_diagnostic::<_, _, ErrMode<ContextError>>(r"^a+");
+        assert_eq!(
+            p.parse_peek(Partial::new("")),
+            Err(ErrMode::Incomplete(Needed::Unknown))
+        );
+
+        let mut input = "b";
+        let err = regex_diagnostic::<_, _, ContextError>(r"^a+")
+            .parse_next(&mut input)
+            .unwrap_err();
+        let cause = err.cause().unwrap().downcast_ref::<RegexMismatch>();
+        assert_eq!(cause.map(RegexMismatch::pattern), Some("^a+"));
+        assert_eq!(input, "b");
+    }
+
+    #[test]
+    fn cached_regex_bytes() {
+        let re =
+            regex_trait::CachedRegex::from(regex::bytes::Regex::new(r"^(?<a>\w)(\w)?").unwrap());
+        assert_eq!(re.captures_len(), 3);
+        let caps = captures::<_, _, ErrMode<ContextError>>(re)
+            .parse_next(&mut &b"x;"[..])
+            .unwrap();
+        assert_eq!(
+            caps.named_iter().collect::<Vec<_>>(),
+            [("a", Some(&b"x"[..]))]
+        );
+        assert_eq!(caps.get(2), None);
+    }
+
+    #[test]
+    fn captures_eq_bytes() {
+        let mut close = captures_eq::<_, _, _, ErrMode<ContextError>>(
+            regex::bytes::Regex::new(r"^</(\w+)>").unwrap(),
+            1,
+            b"p",
+        );
+        assert!(close.parse_peek(&b"</p>"[..]).is_ok());
+        let mut input = &b"</div>"[..];
+        assert!(close.parse_next(&mut input).is_err());
+        assert_eq!(input, b"</div>");
+    }
+
+    #[test]
+    fn regex_full_alternation() {
+        use winnow::stream::Partial;
+        // Without the group, `\z` would only bind to the last alternative.
+        let mut p = regex_full::<_, ErrMode<ContextError>>("a|ab");
+        assert_eq!(p.parse_peek("ab"), Ok(("", "ab")));
+        assert!(p.parse_peek("abc").is_err());
+        let mut p = regex_full::<_, ErrMode<ContextError>>("a|ab");
+        assert_eq!(
+            p.parse_peek(Partial::new("ab")),
+            Err(ErrMode::Incomplete(Needed::Unknown))
+        );
+    }
+
+    #[test]
+    fn longest_match_munch() {
+        use winnow::stream::Partial;
+
+        let mut p = longest_match::<_, _, EmptyError>([r"^=", r"^=="]);
+        assert_eq!(p.parse_peek("==1"), Ok(("1", (1, "=="))));
+        assert_eq!(p.parse_peek("=1"), Ok(("1", (0, "="))));
+        assert!(p.parse_peek("!=").is_err());
+
+        let mut p = longest_match::<_, _, ErrMode<ContextError>>([r"^=", r"^=="]);
+        assert!(p.parse_peek(Partial::new("=")).unwrap_err().is_incomplete());
+        assert_eq!(
+            p.parse_peek(Partial::new("= 1")),
+            Ok((Partial::new(" 1"), (0, "=")))
+        );
+    }
+
+    #[test]
+    fn captures_at_offset() {
+        let mut p = captures_at::<_, _, EmptyError>(r"(\d+)-(\d+)", 3);
+        let mut input = "id=10-20;";
+        let caps = p.parse_next(&mut input).unwrap();
+        assert_eq!(input, ";");
+        assert_eq!(caps.get(0), Some("10-20"));
+        assert_eq!(caps.range(2), Some(3..5));
+        // The match has to start exactly at the offset.
+        assert!(p.parse_peek("id= 10-20").is_err());
+        assert!(p.parse_peek("id").is_err());
+
+        // Look-around sees the prefix.
+        let mut p = captures_at::<_, _, EmptyError>(r"\b\w+", 2);
+        assert!(p.parse_peek("abcd").is_err());
+        assert_eq!(
+            p.parse_peek("a cd").map(|(i, c)| (i, c[0].to_owned())),
+            Ok(("", "cd".to_owned()))
+        );
+    }
+
+    #[test]
+    fn traced_regex_name() {
+        let re = regex::Regex::new(r"^\d+").unwrap();
+        assert_eq!(trace_name(None, &re), r"regex(^\d+)");
+        assert_eq!(trace_name(Some("number"), &re), "number");
+        assert_eq!(
+            traced_regex::<_, _, EmptyError>(None, r"^\d+").parse_peek("42abc"),
+            Ok(("abc", "42"))
+        );
+    }
+
+    #[test]
+    fn captures_cow_borrows_untouched() {
+        fn unescape(s: &str) -> Cow<'_, str> {
+            if s.contains('\\') {
+                Cow::Owned(s.replace('\\', ""))
+            } else {
+                Cow::Borrowed(s)
+            }
+        }
+        let mut p = captures_cow::<_, _, _, EmptyError>(r"^(\w*)(?:\\(\w))?", unescape);
+        let (_, groups) = p.parse_peek("ab;").unwrap();
+        assert!(matches!(groups[0], Some(Cow::Borrowed("ab"))));
+        assert!(groups[2].is_none());
+        let (_, groups) = p.parse_peek(r"ab\c;").unwrap();
+        assert!(matches!(&groups[0], Some(Cow::Owned(s)) if s == "abc"));
+        assert!(matches!(groups[1], Some(Cow::Borrowed("ab"))));
+    }
+
+    #[test]
+    fn regex_line_col_positions() {
+        use winnow::stream::LocatingSlice;
+
+        let mut word = regex_line_col::<_, _, EmptyError>(r"^\w+");
+        let mut input = LocatingSlice::new("héllo\nwörld");
+        assert_eq!(word.parse_next(&mut input), Ok(("héllo", 1, 1)));
+        input.next_token();
+        assert_eq!(word.parse_next(&mut input), Ok(("wörld", 2, 1)));
+
+        // A match before the cached one is counted from the start again.
+        let mut input = LocatingSlice::new("ab cd");
+        input.next_slice(3);
+        assert_eq!(word.parse_next(&mut input), Ok(("cd", 1, 4)));
+        input.reset_to_start();
+        assert_eq!(word.parse_next(&mut input), Ok(("ab", 1, 1)));
+    }
+
+    #[test]
+    fn regex_line_col_reused_on_new_input() {
+        use winnow::stream::LocatingSlice;
+
+        let mut word = regex_line_col::<_, _, EmptyError>(r"^\w+");
+        let mut input = LocatingSlice::new("a\nb\nc");
+        input.next_slice(4);
+        assert_eq!(word.parse_next(&mut input), Ok(("c", 3, 1)));
+
+        let mut input = LocatingSlice::new("xxxxxx yy");
+        input.next_slice(7);
+        word.reset();
+        assert_eq!(word.parse_next(&mut input), Ok(("yy", 1, 8)));
+    }
+
+    #[test]
+    fn required_group_absent() {
+        let mut p = required_group::<_, _, ContextError>(r"^(?:(\d+)|x)", 1);
+        let mut input = "x";
+        assert!(p.parse_next(&mut input).is_err());
+        assert_eq!(input, "x");
+        assert_eq!(p.parse_peek("7x"), Ok(("x", "7")));
+
+        let mut p = required_group::<_, _, ContextError>(r"^(?:(\d+)|x)", 2);
+        assert!(p.parse_peek("7").is_err());
+    }
+
+    #[test]
+    fn regex_completeness_partial() {
+        use winnow::stream::Partial;
+
+        let mut p = regex_completeness::<_, _, ErrMode<ContextError>>(r"^\d+");
+        assert_eq!(
+            p.parse_peek(Partial::new("12")),
+            Ok((Partial::new(""), ("12", Completeness::MaybePrefix)))
+        );
+        assert!(p.parse_peek(Partial::new("")).unwrap_err().is_incomplete());
+
+        let mut p = regex_completeness::<_, _, EmptyError>(r"^\d+");
+        assert_eq!(p.parse_peek("12"), Ok(("", ("12", Completeness::Definite))));
+        assert!(p.parse_peek("x").is_err());
+    }
+
+    #[test]
+    fn regex_with_context_clamps() {
+        use winnow::stream::LocatingSlice;
+
+        let mut p = regex_with_context::<_, _, EmptyError>(r"^\d+", 10, 10);
+        let mut input = LocatingSlice::new("12 ab");
+        assert_eq!(p.parse_next(&mut input), Ok(("12", "", " ab")));
+
+        // Context stops short of a character that does not fit.
+        let mut p = regex_with_context::<_, _, EmptyError>(r"^\d+", 1, 1);
+        let mut input = LocatingSlice::new("é1é");
+        input.next_token();
+        assert_eq!(p.parse_next(&mut input), Ok(("1", "", "")));
+        assert_eq!(*input, "é");
+    }
+
+    #[test]
+    fn count_matches_stops_on_empty() {
+        let mut p = count_matches::<_, _, EmptyError>(r"^a*");
+        assert_eq!(p.parse_peek("aab"), Ok(("b", 1)));
+        assert_eq!(p.parse_peek("b"), Ok(("b", 0)));
+
+        use winnow::stream::Partial;
+        let mut p = count_matches::<_, _, ErrMode<ContextError>>(r"^\d,");
+        assert!(
+            p.parse_peek(Partial::new("1,2"))
+                .unwrap_err()
+                .is_incomplete()
+        );
+        let mut input = Partial::new("1,2,;");
+        let _ = input.complete();
+        assert_eq!(p.parse_next(&mut input), Ok(2));
+        assert_eq!(*input, ";");
+    }
+
+    #[test]
+    fn any_of_first_wins() {
+        let res = [r"^=", r"^==", r"^!="].map(|p| regex::Regex::new(p).unwrap());
+        let mut p = any_of::<_, EmptyError>(res.to_vec());
+        assert_eq!(p.parse_peek("==1"), Ok(("=1", (0, "="))));
+        assert_eq!(p.parse_peek("!=1"), Ok(("1", (2, "!="))));
+        assert!(p.parse_peek("<").is_err());
+        assert!(any_of::<_, EmptyError>(vec![]).parse_peek("x").is_err());
+    }
+
+    #[test]
+    fn regex_with_pattern_bytes() {
+        let mut p =
+            regex_with_pattern::<_, _, EmptyError>(regex::bytes::Regex::new(r"^\d+").unwrap());
+        let (rest, (digits, pattern)) = p.parse_peek(&b"12;"[..]).unwrap();
+        assert_eq!((rest, digits, &*pattern), (&b";"[..], &b"12"[..], r"^\d+"));
+        assert!(p.parse_peek(&b";"[..]).is_err());
+    }
+
+    #[test]
+    fn regex_skip_bom_partial() {
+        use winnow::stream::Partial;
+
+        let mut p = regex_skip_bom::<_, _, EmptyError>(r"^\w+");
+        assert_eq!(p.parse_peek("\u{FEFF}ab;"), Ok((";", "ab")));
+        assert_eq!(p.parse_peek("ab;"), Ok((";", "ab")));
+        let mut input = "\u{FEFF};";
+        assert!(p.parse_next(&mut input).is_err());
+        assert_eq!(input, "\u{FEFF};");
+
+        let re = regex::bytes::Regex::new(r"^\w+").unwrap();
+        let mut p = regex_skip_bom::<_, _, ErrMode<ContextError>>(re);
+        assert_eq!(
+            p.parse_peek(Partial::new(&b"\xef\xbb"[..])),
+            Err(ErrMode::Incomplete(Needed::new(1)))
+        );
+        assert_eq!(
+            p.parse_peek(Partial::new(&b"\xef\xbb\xbfab;"[..])),
+            Ok((Partial::new(&b";"[..]), &b"ab"[..]))
+        );
+    }
+
+    #[test]
+    fn named_ranges_absolute() {
+        use winnow::stream::LocatingSlice;
+
+        let mut input = LocatingSlice::new("let x = 42");
+        input.next_slice(4);
+        let m = rich_captures::<_, _, EmptyError>(r"^(?<name>\w+) = (?<value>\d+)")
+            .parse_next(&mut input)
+            .unwrap();
+        let spans: Vec<_> = m
+            .captures()
+            .named_ranges()
+            .map(|(name, range)| {
+                (
+                    name,
+                    range.map(|r| r.start + m.offset()..r.end + m.offset()),
+                )
+            })
+            .collect();
+        assert_eq!(spans, [("name", Some(4..5)), ("value", Some(8..10))]);
     }
 
     #[test]
-    fn partial() {
+    fn regex_eof_trailing() {
         use winnow::stream::Partial;
-        fn partial<'i>(i: &mut Partial<&'i [u8]>) -> ModalResult<&'i [u8], ContextError> {
-            regex(regex::bytes::Regex::new(r"^\d+").unwrap()).parse_next(i)
+
+        let mut p = regex_eof::<_, _, EmptyError>(r"^[a-z]+");
+        let mut input = "abc;";
+        assert!(p.parse_next(&mut input).is_err());
+        assert_eq!(input, "abc;");
+        assert_eq!(p.parse_peek("abc"), Ok(("", "abc")));
+
+        let mut p = regex_eof::<_, _, ErrMode<ContextError>>(r"^[a-z]+");
+        assert!(
+            p.parse_peek(Partial::new("abc"))
+                .unwrap_err()
+                .is_incomplete()
+        );
+        let mut input = Partial::new("abc");
+        let _ = input.complete();
+        assert_eq!(p.parse_next(&mut input), Ok("abc"));
+    }
+
+    #[cfg(feature = "hash")]
+    #[test]
+    fn regex_hashed_dedup() {
+        let mut p = regex_hashed::<_, _, EmptyError>(r"^\w+");
+        let (_, (_, a)) = p.parse_peek("abc def").unwrap();
+        let (_, (_, b)) = p.parse_peek("abd").unwrap();
+        assert_ne!(a, b);
+
+        let re = regex::bytes::Regex::new(r"^\w+").unwrap();
+        let mut p = regex_hashed::<_, _, EmptyError>(re);
+        assert_eq!(p.parse_peek(&b"abc;"[..]).unwrap().1.1, a);
+    }
+
+    #[test]
+    fn regex_checkpointed_rollback() {
+        use winnow::stream::LocatingSlice;
+
+        let mut p = regex_checkpointed::<_, _, EmptyError>(r"^\w+");
+        let mut input = LocatingSlice::new("ab cd");
+        let _ = p.parse_next(&mut input).unwrap();
+        input.next_token();
+        let (word, checkpoint) = p.parse_next(&mut input).unwrap();
+        assert_eq!((word, input.current_token_start()), ("cd", 5));
+        input.reset(&checkpoint);
+        assert_eq!(input.current_token_start(), 3);
+    }
+
+    #[derive(Default)]
+    struct Symbols(Vec<String>);
+
+    impl Interner for Symbols {
+        type Symbol = u32;
+
+        fn intern(&mut self, token: &str) -> u32 {
+            match self.0.iter().position(|t| t == token) {
+                Some(i) => i as u32,
+                None => {
+                    self.0.push(token.to_owned());
+                    self.0.len() as u32 - 1
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn regex_intern_repeated_identifiers() {
+        let mut symbols = Symbols::default();
+        let mut input = "foo bar foo baz bar";
+        {
+            let mut ident = regex_intern::<_, _, _, EmptyError>(r"^\w+", &mut symbols);
+            let mut syms = vec![];
+            while let Ok(sym) = ident.parse_next(&mut input) {
+                syms.push(sym);
+                input = input.trim_start();
+            }
+            assert_eq!(syms, [0, 1, 0, 2, 1]);
+            assert!(ident.parse_next(&mut "!").is_err());
         }
+        assert_eq!(symbols.0, ["foo", "bar", "baz"]);
+    }
+
+    #[test]
+    fn captures_named_only_skips_anonymous() {
+        let mut p = captures_named_only::<_, _, EmptyError>(
+            r"^(?<date>(\d{4})-(\d{2}))(?:T(?<time>\d{2}:\d{2}))?",
+        );
+        let mut input = "2024-05T10:30 rest";
+        let caps = p.parse_next(&mut input).unwrap();
+        assert_eq!(input, " rest");
+        assert_eq!(caps.as_match(), &"2024-05T10:30");
         assert_eq!(
-            partial.parse_peek(Partial::new(&b"123abc"[..])),
-            Ok((Partial::new(&b"abc"[..]), &b"123"[..]))
+            caps.iter().collect::<Vec<_>>(),
+            [("date", Some("2024-05")), ("time", Some("10:30"))]
+        );
+        assert_eq!(caps.range("time"), Some(8..13));
+
+        let caps = p.parse_next(&mut "2024-06").unwrap();
+        assert_eq!(caps.get("date"), Some("2024-06"));
+        assert_eq!(caps.get("time"), None);
+        assert_eq!(caps.get("missing"), None);
+    }
+
+    #[test]
+    fn captures_named_only_bytes() {
+        let re = regex::bytes::Regex::new(r"^(\w+)=(?<v>\d+)").unwrap();
+        let caps = captures_named_only::<_, _, EmptyError>(re)
+            .parse_next(&mut &b"k=12;"[..])
+            .unwrap();
+        assert_eq!(caps.get("v"), Some(&b"12"[..]));
+        assert_eq!(caps.len(), 1);
+    }
+
+    #[test]
+    fn regex_parse_i64() {
+        let mut int = regex_parse::<_, _, i64, ContextError>(r"^-?\d+");
+        assert_eq!(int.parse_peek("-17 rest"), Ok((" rest", -17)));
+        assert_eq!(int.parse_peek("0"), Ok(("", 0)));
+        assert!(int.parse_peek("x1").is_err());
+
+        let mut input = "99999999999999999999";
+        let err = int.parse_next(&mut input).unwrap_err();
+        assert!(err.cause().is_some());
+        assert_eq!(input, "99999999999999999999");
+    }
+
+    #[test]
+    fn empty_match_modes() {
+        let mut accept = regex::<_, _, ErrMode<ContextError>>(r"^\d*");
+        assert_eq!(accept.parse_peek("abc"), Ok(("abc", "")));
+
+        let mut reject =
+            regex::<_, _, ErrMode<ContextError>>(r"^\d*").empty_match(EmptyMatch::Reject);
+        assert!(matches!(
+            reject.parse_peek("abc"),
+            Err(ErrMode::Backtrack(_))
+        ));
+        assert_eq!(reject.parse_peek("12abc"), Ok(("abc", "12")));
+
+        let mut error =
+            regex::<_, _, ErrMode<ContextError>>(r"^\d*").empty_match(EmptyMatch::Error);
+        assert_eq!(error.parse_peek("7"), Ok(("", "7")));
+    }
+
+    #[test]
+    fn empty_match_error() {
+        let mut p = regex::<_, _, ContextError>(r"^\d*").empty_match(EmptyMatch::Error);
+        let err = p.parse_peek("abc").unwrap_err();
+        let cause = err.cause().unwrap();
+        assert_eq!(cause.downcast_ref(), Some(&EmptyMatchError));
+    }
+
+    #[test]
+    fn empty_match_in_repeat() {
+        let mut input = "1,2,;";
+        let items: Vec<&str> = winnow::combinator::repeat(
+            0..,
+            regex::<_, _, ErrMode<ContextError>>(r"^\d*,?").empty_match(EmptyMatch::Reject),
+        )
+        .parse_next(&mut input)
+        .unwrap();
+        assert_eq!(items, ["1,", "2,"]);
+        assert_eq!(input, ";");
+    }
+
+    #[test]
+    fn captures_participation_optional_groups() {
+        let mut p = captures_participation::<_, _, EmptyError>(r"^(a)?(b)?(c)?");
+        assert_eq!(p.parse_peek("ac!"), Ok(("!", ("ac", vec![0, 1, 3]))));
+        assert_eq!(p.parse_peek("!"), Ok(("!", ("", vec![0]))));
+
+        let re = regex::bytes::Regex::new(r"^(?:(x)|(y))").unwrap();
+        let mut p = captures_participation::<_, _, EmptyError>(re);
+        assert_eq!(
+            p.parse_peek(&b"yz"[..]),
+            Ok((&b"z"[..], (&b"y"[..], vec![0, 2])))
         );
+    }
+
+    #[test]
+    fn captures_unescaped_quoted() {
+        let mut p = captures_unescaped::<_, _, ErrMode<ContextError>>(r#"^"((?:[^"\\]|\\.)*)""#, 1);
+        assert_eq!(p.parse_peek(r#""a\nb" x"#), Ok((" x", "a\nb".to_owned())));
         assert_eq!(
-            partial.parse_peek(Partial::new(&b"123"[..])),
+            p.parse_peek(r#""\t\\\'\0""#),
+            Ok(("", "\t\\'\0".to_owned()))
+        );
+
+        let mut input = r#""ok\x41""#;
+        let err = p.parse_next(&mut input).unwrap_err().into_inner().unwrap();
+        let cause = err.cause().unwrap().downcast_ref::<InvalidEscape>();
+        assert_eq!(cause.map(InvalidEscape::offset), Some(2));
+        assert_eq!(input, r#""ok\x41""#);
+    }
+
+    #[test]
+    fn unescape_trailing_backslash() {
+        assert_eq!(unescape(r"ab\"), Err(InvalidEscape { offset: 2 }));
+        assert_eq!(unescape("é\\n"), Ok("é\n".to_owned()));
+    }
+
+    #[test]
+    fn regex_newline_count_two_lines() {
+        let mut input = "first\nsecond\nrest";
+        let mut line = 1;
+        let (text, newlines) = regex_newline_count::<_, _, EmptyError>(r"^\w+\n\w+\n")
+            .parse_next(&mut input)
+            .unwrap();
+        line += newlines;
+        assert_eq!((text, line, input), ("first\nsecond\n", 3, "rest"));
+
+        let re = regex::bytes::Regex::new(r"^(?-u:[^;])*").unwrap();
+        assert_eq!(
+            regex_newline_count::<_, _, EmptyError>(re).parse_peek(&b"\xff\n\n;"[..]),
+            Ok((&b";"[..], (&b"\xff\n\n"[..], 2)))
+        );
+    }
+
+    #[test]
+    fn group_then_parses_value() {
+        use winnow::ascii::dec_int;
+
+        let mut p =
+            group_then::<_, _, _, i64, ErrMode<ContextError>>(r"^(\w+)=\((.*?)\)", 2, dec_int);
+        assert_eq!(p.parse_peek("key=(-12) rest"), Ok((" rest", -12)));
+
+        let mut input = "key=(1 2)";
+        assert!(matches!(
+            p.parse_next(&mut input),
+            Err(ErrMode::Backtrack(_))
+        ));
+        assert_eq!(input, "key=(1 2)");
+
+        let mut cut = group_then::<_, _, _, i64, ErrMode<ContextError>>(
+            r"^\((.*?)\)",
+            1,
+            winnow::combinator::cut_err(dec_int),
+        );
+        assert!(matches!(cut.parse_peek("(x)"), Err(ErrMode::Cut(_))));
+    }
+
+    #[test]
+    fn group_then_partial() {
+        use winnow::ascii::dec_uint;
+        use winnow::stream::Partial;
+
+        let mut p = group_then::<_, _, _, u8, ErrMode<ContextError>>(r"^<(\d+)>", 1, dec_uint);
+        assert_eq!(
+            p.parse_peek(Partial::new("<7>;")),
+            Ok((Partial::new(";"), 7))
+        );
+        assert!(
+            p.parse_peek(Partial::new("<7"))
+                .unwrap_err()
+                .is_incomplete()
+        );
+    }
+
+    #[test]
+    fn parser_capture_names() {
+        let p = regex::<&str, _, EmptyError>(r"^(?<a>x)(y)(?<b>z)?");
+        let names: Vec<_> = p.capture_names().collect();
+        assert_eq!(names, [None, Some("a"), None, Some("b")]);
+
+        let re = regex::bytes::Regex::new(r"^(?<len>\d+):").unwrap();
+        let p = captures::<&[u8], _, EmptyError>(re);
+        assert_eq!(p.capture_names().collect::<Vec<_>>(), [None, Some("len")]);
+    }
+
+    #[test]
+    fn regex_trim_even_length() {
+        let mut p = regex_trim::<_, _, _, EmptyError>(r"^a+", |m: &str| m.len() & !1);
+        assert_eq!(p.parse_peek("aaaaab"), Ok(("ab", "aaaa")));
+        assert_eq!(p.parse_peek("aab"), Ok(("b", "aa")));
+        assert_eq!(p.parse_peek("ab"), Ok(("ab", "")));
+
+        let re = regex::bytes::Regex::new(r"^\d+").unwrap();
+        let mut p = regex_trim::<_, _, _, EmptyError>(re, |_: &[u8]| 100);
+        assert_eq!(p.parse_peek(&b"123x"[..]), Ok((&b"x"[..], &b"123"[..])));
+    }
+
+    #[test]
+    fn lexer_table_tokens() {
+        let table = LexerTable::new([
+            ("if", r"^if\b"),
+            ("number", r"^\d+"),
+            ("ident", r"^[a-z_]\w*"),
+            ("ws", r"^\s+"),
+        ])
+        .unwrap();
+        assert_eq!((table.len(), table.index_of("ident")), (4, Some(2)));
+        assert_eq!(table.name(3), Some("ws"));
+
+        let mut lex = &table;
+        let mut input = "if iffy 7";
+        let mut tokens = vec![];
+        while let Ok((name, text)) = Parser::<_, _, EmptyError>::parse_next(&mut lex, &mut input) {
+            if name != "ws" {
+                tokens.push((name, text));
+            }
+        }
+        assert_eq!(tokens, [("if", "if"), ("ident", "iffy"), ("number", "7")]);
+        assert_eq!(input, "");
+    }
+
+    #[test]
+    fn lexer_table_first_error() {
+        let err = LexerTable::new([("ok", r"^a"), ("bad", r"^("), ("worse", r"^[")]).unwrap_err();
+        assert!(err.to_string().contains("unclosed group"));
+
+        let bytes = [("nl", r"^\n")].map(|(n, p)| (n, regex::bytes::Regex::new(p).unwrap()));
+        let table = LexerTable::new(bytes).unwrap();
+        let mut lex = &table;
+        assert_eq!(
+            Parser::<_, _, EmptyError>::parse_peek(&mut lex, &b"\nx"[..]),
+            Ok((&b"x"[..], ("nl", &b"\n"[..])))
+        );
+    }
+
+    #[test]
+    fn regex_end_offset_is_consumed_length() {
+        let mut input = "12345 rest";
+        let before = input;
+        let (digits, end) = regex_end_offset::<_, _, EmptyError>(r"^\d+")
+            .parse_next(&mut input)
+            .unwrap();
+        assert_eq!((digits, end), ("12345", 5));
+        assert_eq!(end, input.offset_from(&before));
+
+        let mut input = LocatingSlice::new("ab,cd");
+        let mut p = regex_end_offset::<_, _, EmptyError>(r"^\w+,?");
+        assert_eq!(p.parse_next(&mut input), Ok(("ab,", 3)));
+        assert_eq!(p.parse_next(&mut input), Ok(("cd", 2)));
+        assert_eq!(input.current_token_start(), 5);
+    }
+
+    #[test]
+    fn captures_or_default_group() {
+        let mut p = captures_or::<_, _, _, EmptyError>(r"^(\d+)(?:/(\d+))?", [(1, ""), (2, "1")]);
+        assert_eq!(p.parse_peek("10/3 x"), Ok((" x", vec!["10", "3"])));
+        assert_eq!(p.parse_peek("10 x"), Ok((" x", vec!["10", "1"])));
+        assert!(p.parse_peek("/3").is_err());
+
+        let re = regex::bytes::Regex::new(r"^(?<k>\w+)(?:=(?<v>\w+))?").unwrap();
+        let mut p = captures_or::<_, _, _, EmptyError>(re, [("v", &b"true"[..])]);
+        assert_eq!(
+            p.parse_peek(&b"flag;"[..]),
+            Ok((&b";"[..], vec![&b"true"[..]]))
+        );
+    }
+
+    #[test]
+    #[should_panic = "no capture group \"missing\""]
+    fn captures_or_unknown_group() {
+        let _ = captures_or::<&str, _, _, EmptyError>(r"^(\d+)", [("missing", "0")]);
+    }
+
+    #[test]
+    fn regex_span_absolute() {
+        let mut input = LocatingSlice::new("  key = välue;");
+        let mut word = regex_span::<_, _, EmptyError>(r"^\w+");
+        let mut ws = regex::<_, _, EmptyError>(r"^[\s=]*");
+
+        ws.parse_next(&mut input).unwrap();
+        assert_eq!(word.parse_next(&mut input), Ok(("key", 2..5)));
+        ws.parse_next(&mut input).unwrap();
+        assert_eq!(word.parse_next(&mut input), Ok(("välue", 8..14)));
+        assert!(word.parse_next(&mut input).is_err());
+    }
+
+    #[test]
+    fn keyword_ci() {
+        const KEYWORDS: &[&str] = &["SEL", "SELECT", "select", "From"];
+        let mut p = regex_keyword_ci::<_, EmptyError>(KEYWORDS);
+        assert_eq!(p.parse_peek("sElEcT *"), Ok((" *", ("sElEcT", 1))));
+        assert_eq!(p.parse_peek("sel;"), Ok((";", ("sel", 0))));
+        assert_eq!(p.parse_peek("FROM"), Ok(("", ("FROM", 3))));
+        assert!(p.parse_peek("where").is_err());
+        assert!(
+            regex_keyword_ci::<_, EmptyError>(&[])
+                .parse_peek("")
+                .is_err()
+        );
+
+        use winnow::stream::Partial;
+        let mut p = regex_keyword_ci::<_, ErrMode<EmptyError>>(KEYWORDS);
+        assert!(
+            p.parse_peek(Partial::new("fro"))
+                .unwrap_err()
+                .is_incomplete()
+        );
+    }
+
+    #[test]
+    fn size_hint_counts_bytes() {
+        use winnow::stream::Partial;
+        let mut p = regex_size_hint::<_, _, ErrMode<EmptyError>>(r"^日本語\d");
+        assert_eq!(
+            p.parse_peek(Partial::new("日")),
+            Err(ErrMode::Incomplete(Needed::new(7)))
+        );
+        assert_eq!(
+            p.parse_peek(Partial::new("日本語")),
+            Err(ErrMode::Incomplete(Needed::new(1)))
+        );
+        assert_eq!(
+            p.parse_peek(Partial::new("日本語1;")),
+            Ok((Partial::new(";"), "日本語1"))
+        );
+        // Long enough but not matching yet: no better hint than `regex` gives.
+        assert_eq!(
+            p.parse_peek(Partial::new("日本語日")),
+            Err(ErrMode::Incomplete(Needed::Unknown))
+        );
+        let mut complete = regex_size_hint::<&str, _, ErrMode<EmptyError>>(r"^日本語\d");
+        assert_eq!(
+            complete.parse_peek("日"),
+            Err(ErrMode::Backtrack(EmptyError))
+        );
+
+        // `ſ` takes 2 bytes, but folds to the 1-byte `s`.
+        let mut p = regex_size_hint::<_, _, ErrMode<EmptyError>>(r"^ſ");
+        assert_eq!(
+            p.parse_peek(Partial::new("")),
+            Err(ErrMode::Incomplete(Needed::new(1)))
+        );
+    }
+
+    #[test]
+    fn captures_ranges_line_up() {
+        let mut input = "800x600;";
+        let start = input;
+        let ranges = captures_ranges::<_, _, EmptyError>(r"^(\d+)x(\d+)")
+            .parse_next(&mut input)
+            .unwrap();
+        let slice = &start[..input.offset_from(&start)];
+        assert_eq!(slice, "800x600");
+        let groups: Vec<_> = ranges.into_iter().map(|r| &slice[r.unwrap()]).collect();
+        assert_eq!(groups, ["800x600", "800", "600"]);
+    }
+
+    #[test]
+    fn tokenize_priority() {
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        enum Kind {
+            If,
+            Ident,
+            Eq,
+            EqEq,
+        }
+        let table = [
+            (Kind::If, r"^if"),
+            (Kind::Ident, r"^\w+"),
+            (Kind::Eq, r"^="),
+            (Kind::EqEq, r"^=="),
+        ];
+        let mut first = tokenize::<_, _, _, EmptyError>(table);
+        assert_eq!(first.parse_peek("iffy"), Ok(("fy", (Kind::If, "if"))));
+        assert_eq!(first.parse_peek("=="), Ok(("=", (Kind::Eq, "="))));
+        assert!(first.parse_peek("+").is_err());
+
+        let mut longest = tokenize::<_, _, _, EmptyError>(table).longest();
+        assert_eq!(longest.parse_peek("iffy"), Ok(("", (Kind::Ident, "iffy"))));
+        assert_eq!(longest.parse_peek("if x"), Ok((" x", (Kind::If, "if"))));
+        assert_eq!(longest.parse_peek("=="), Ok(("", (Kind::EqEq, "=="))));
+    }
+
+    #[test]
+    fn scan_three_matches() {
+        let mut input = "x=1; y=22; z=333;";
+        let mut scanner = scan(r"(\w)=(\d+)", &input);
+        assert_eq!(scanner.next(), Some(("x=1", 0)));
+        assert_eq!(scanner.offset(), 3);
+        assert_eq!(scanner.next(), Some(("y=22", 5)));
+        assert_eq!(scanner.next(), Some(("z=333", 11)));
+        assert_eq!(scanner.next(), None);
+        // Scanning did not consume the input.
+        assert_eq!(input, "x=1; y=22; z=333;");
+        let _ = input.next_slice(11);
+        assert_eq!(scan(r"\d+", &input).collect::<Vec<_>>(), [("333", 2)]);
+
+        let empty: Vec<_> = scan(r"a*", &"baa").map(|(_, at)| at).collect();
+        assert_eq!(empty, [0, 1]);
+    }
+
+    #[test]
+    fn split() {
+        use winnow::combinator::repeat;
+        fn fields<'i>(s: &mut &'i str) -> ModalResult<(Vec<&'i str>, &'i str)> {
+            (repeat(0.., split_iter(",")), split_last(",")).parse_next(s)
+        }
+        let re = regex::Regex::new(",").unwrap();
+        for hay in ["", ",", "a", "a,,b", ",a,"] {
+            let mut expected: Vec<&str> = re.split(hay).collect();
+            let last = expected.pop().unwrap();
+            assert_eq!(fields.parse_peek(hay), Ok(("", (expected, last))));
+        }
+    }
+
+    #[test]
+    #[should_panic = "separator must not match the empty string"]
+    #[cfg(debug_assertions)]
+    fn split_iter_empty_separator() {
+        let mut p = split_iter::<_, _, ErrMode<ContextError>>(",*");
+        let _ = p.parse_peek("a,b");
+    }
+
+    #[test]
+    fn split_partial() {
+        use winnow::stream::Partial;
+        fn field<'i>(i: &mut Partial<&'i str>) -> ModalResult<&'i str> {
+            split_iter(",+").parse_next(i)
+        }
+        assert_eq!(
+            field.parse_peek(Partial::new("a,,b")),
+            Ok((Partial::new("b"), "a"))
+        );
+        assert_eq!(
+            field.parse_peek(Partial::new("a,")),
+            Err(ErrMode::Incomplete(Needed::Unknown))
+        );
+        assert_eq!(
+            field.parse_peek(Partial::new("ab")),
             Err(ErrMode::Incomplete(Needed::Unknown))
         );
     }
@@ -344,4 +8080,48 @@ mod tests {
         assert!(re.find_at("1abc123", 1).is_some());
         assert!(re.find("abc123").is_some());
     }
+
+    /// A regex that implements only the required methods of [`Regex`].
+    struct Minimal(regex::Regex);
+
+    impl Regex for Minimal {
+        type Haystack<'h> = &'h str;
+        type CaptureLocations = regex::CaptureLocations;
+
+        fn capture_locations(&self) -> regex::CaptureLocations {
+            self.0.capture_locations()
+        }
+
+        fn captures_read(
+            &self,
+            locs: &mut regex::CaptureLocations,
+            haystack: &str,
+        ) -> Option<(usize, usize)> {
+            let m = self.0.captures_read(locs, haystack)?;
+            Some((m.start(), m.end()))
+        }
+    }
+
+    impl RegexPattern for Minimal {
+        type Error = core::convert::Infallible;
+        type Output = Self;
+
+        fn try_into_regex(self) -> Result<Self, Self::Error> {
+            Ok(self)
+        }
+    }
+
+    #[test]
+    fn regex_trait_defaults() {
+        let re = Minimal(regex::Regex::new(r"(?<n>\d+)(px)?").unwrap());
+        assert_eq!(re.as_str(), "");
+        assert_eq!(re.capture_names().collect::<Vec<_>>(), [None, None, None]);
+        assert_eq!(re.find("ab12px"), Some((2, 6)));
+        assert!(re.is_match_at("ab12", 0));
+        // Searching from an offset is not available without an override.
+        assert!(!re.is_match_at("12ab", 1));
+
+        let mut p = regex::<_, _, EmptyError>(Minimal(regex::Regex::new(r"^\d+").unwrap()));
+        assert_eq!(p.parse_peek("42;"), Ok((";", "42")));
+    }
 }
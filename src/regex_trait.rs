@@ -1,3 +1,5 @@
+use crate::{Error, RegexPattern};
+
 /// see
 /// [`regex::CaptureLocations`](https://docs.rs/regex/latest/regex/struct.CaptureLocations.html)
 /// for more details
@@ -34,16 +36,104 @@ impl CaptureLocations for regex::bytes::CaptureLocations {
     }
 }
 
+/// Converts a stream slice into the haystack a [`Regex`] searches.
+///
+/// Parsers normally require the regex haystack to be the stream's `Slice` type itself. A stream
+/// whose slices are a domain newtype around `&str` or `&[u8]` can implement this for the
+/// newtype and use [`regex_via_haystack`](crate::regex_via_haystack), which matches on the
+/// converted haystack but still returns the newtype slice.
+pub trait IntoHaystack<'h> {
+    type Haystack;
+
+    fn into_haystack(self) -> Self::Haystack;
+}
+
+impl<'h> IntoHaystack<'h> for &'h str {
+    type Haystack = &'h str;
+
+    #[inline(always)]
+    fn into_haystack(self) -> Self::Haystack {
+        self
+    }
+}
+
+impl<'h> IntoHaystack<'h> for &'h [u8] {
+    type Haystack = &'h [u8];
+
+    #[inline(always)]
+    fn into_haystack(self) -> Self::Haystack {
+        self
+    }
+}
+
+/// A compiled regex the parsers of this crate can run.
+///
+/// Only [`capture_locations`](Regex::capture_locations) and
+/// [`captures_read`](Regex::captures_read) are required; the other methods have defaults built
+/// on them, which an engine can override with a faster or more complete search.
 pub trait Regex {
     type Haystack<'h>;
     type CaptureLocations: CaptureLocations;
 
     fn capture_locations(&self) -> Self::CaptureLocations;
+    /// Returns the pattern the regex was compiled from, or an empty string if it is unknown.
+    fn as_str(&self) -> &str {
+        ""
+    }
+    /// Returns the pattern the regex was compiled from, for recording which regex matched.
+    ///
+    /// This is [`as_str`](Regex::as_str) under a name that does not suggest the regex is a
+    /// string.
+    fn pattern(&self) -> &str {
+        self.as_str()
+    }
+    /// Returns the names of all capture groups in pattern order, `None` for unnamed groups.
+    ///
+    /// The default reports every group as unnamed.
+    fn capture_names(&self) -> impl Iterator<Item = Option<&str>> {
+        (0..self.capture_locations().len()).map(|_| None)
+    }
     fn captures_read(
         &self,
         locs: &mut Self::CaptureLocations,
         haystack: Self::Haystack<'_>,
     ) -> Option<(usize, usize)>;
+    /// Like [`captures_read`](Regex::captures_read), but starts the search at `at`.
+    ///
+    /// The text before `at` is still visible to look-around assertions such as `^` and `\b`,
+    /// and the returned bounds and capture locations are relative to the whole `haystack`.
+    ///
+    /// The default can only search from the start: it runs
+    /// [`captures_read`](Regex::captures_read) when `at` is `0` and finds no match otherwise,
+    /// since an arbitrary haystack type cannot be sliced. The engines in this crate override it.
+    fn captures_read_at(
+        &self,
+        locs: &mut Self::CaptureLocations,
+        haystack: Self::Haystack<'_>,
+        at: usize,
+    ) -> Option<(usize, usize)> {
+        if at == 0 {
+            self.captures_read(locs, haystack)
+        } else {
+            None
+        }
+    }
+    /// Returns the bounds of the leftmost match in `haystack`, without computing capture
+    /// locations.
+    ///
+    /// The default runs [`captures_read`](Regex::captures_read) and discards the locations.
+    fn find(&self, haystack: Self::Haystack<'_>) -> Option<(usize, usize)> {
+        self.captures_read(&mut self.capture_locations(), haystack)
+    }
+    /// Returns whether the regex matches anywhere in `haystack` at or after `at`, without
+    /// computing capture locations.
+    ///
+    /// The default runs [`captures_read_at`](Regex::captures_read_at) and discards the
+    /// locations.
+    fn is_match_at(&self, haystack: Self::Haystack<'_>, at: usize) -> bool {
+        self.captures_read_at(&mut self.capture_locations(), haystack, at)
+            .is_some()
+    }
 }
 
 impl Regex for regex::Regex {
@@ -55,6 +145,16 @@ impl Regex for regex::Regex {
         regex::Regex::capture_locations(self)
     }
 
+    #[inline]
+    fn as_str(&self) -> &str {
+        regex::Regex::as_str(self)
+    }
+
+    #[inline]
+    fn capture_names(&self) -> impl Iterator<Item = Option<&str>> {
+        regex::Regex::capture_names(self)
+    }
+
     #[inline]
     fn captures_read(
         &self,
@@ -63,6 +163,26 @@ impl Regex for regex::Regex {
     ) -> Option<(usize, usize)> {
         regex::Regex::captures_read(self, locs, haystack).map(|c| (c.start(), c.end()))
     }
+
+    #[inline]
+    fn captures_read_at(
+        &self,
+        locs: &mut Self::CaptureLocations,
+        haystack: Self::Haystack<'_>,
+        at: usize,
+    ) -> Option<(usize, usize)> {
+        regex::Regex::captures_read_at(self, locs, haystack, at).map(|c| (c.start(), c.end()))
+    }
+
+    #[inline]
+    fn find(&self, haystack: Self::Haystack<'_>) -> Option<(usize, usize)> {
+        regex::Regex::find(self, haystack).map(|m| (m.start(), m.end()))
+    }
+
+    #[inline]
+    fn is_match_at(&self, haystack: Self::Haystack<'_>, at: usize) -> bool {
+        regex::Regex::is_match_at(self, haystack, at)
+    }
 }
 
 impl Regex for regex::bytes::Regex {
@@ -74,6 +194,16 @@ impl Regex for regex::bytes::Regex {
         regex::bytes::Regex::capture_locations(self)
     }
 
+    #[inline]
+    fn as_str(&self) -> &str {
+        regex::bytes::Regex::as_str(self)
+    }
+
+    #[inline]
+    fn capture_names(&self) -> impl Iterator<Item = Option<&str>> {
+        regex::bytes::Regex::capture_names(self)
+    }
+
     #[inline]
     fn captures_read(
         &self,
@@ -82,4 +212,148 @@ impl Regex for regex::bytes::Regex {
     ) -> Option<(usize, usize)> {
         regex::bytes::Regex::captures_read(self, locs, haystack).map(|c| (c.start(), c.end()))
     }
+
+    #[inline]
+    fn captures_read_at(
+        &self,
+        locs: &mut Self::CaptureLocations,
+        haystack: Self::Haystack<'_>,
+        at: usize,
+    ) -> Option<(usize, usize)> {
+        regex::bytes::Regex::captures_read_at(self, locs, haystack, at)
+            .map(|c| (c.start(), c.end()))
+    }
+
+    #[inline]
+    fn find(&self, haystack: Self::Haystack<'_>) -> Option<(usize, usize)> {
+        regex::bytes::Regex::find(self, haystack).map(|m| (m.start(), m.end()))
+    }
+
+    #[inline]
+    fn is_match_at(&self, haystack: Self::Haystack<'_>, at: usize) -> bool {
+        regex::bytes::Regex::is_match_at(self, haystack, at)
+    }
+}
+
+/// A [`Regex`] wrapper that records the number of capture groups when it is built.
+///
+/// Every trait method delegates to the wrapped regex, so `CachedRegex` can be passed anywhere a
+/// regex is accepted. [`CachedRegex::captures_len`] is then available without allocating, e.g.
+/// to size buffers ahead of parsing. It also serves as a small example of implementing
+/// [`Regex`] on top of another backend.
+///
+/// # Example
+///
+/// ```
+/// use winnow::prelude::*;
+/// use winnow_regex::{captures, regex_trait::CachedRegex};
+///
+/// let re = CachedRegex::new(r"^(\d+)x(\d+)").unwrap();
+/// assert_eq!(re.captures_len(), 3);
+///
+/// fn dims(s: &mut &str, re: &CachedRegex) -> ModalResult<Vec<String>> {
+///     let mut dims = Vec::with_capacity(re.captures_len() - 1);
+///     let caps = captures(re.clone()).parse_next(s)?;
+///     dims.extend(caps.iter().skip(1).flatten().map(str::to_owned));
+///     Ok(dims)
+/// }
+///
+/// assert_eq!(dims(&mut "800x600", &re).unwrap(), ["800", "600"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CachedRegex<R = regex::Regex> {
+    re: R,
+    captures_len: usize,
+}
+
+impl CachedRegex {
+    /// Compiles `pattern` with [`regex::Regex::new`].
+    pub fn new(pattern: &str) -> Result<Self, Error> {
+        Ok(Self::from(regex::Regex::new(pattern)?))
+    }
+}
+
+impl<R: Regex> CachedRegex<R> {
+    /// Returns the number of groups in the pattern, including the implicit group 0.
+    pub fn captures_len(&self) -> usize {
+        self.captures_len
+    }
+
+    /// Returns the wrapped regex.
+    pub fn get_ref(&self) -> &R {
+        &self.re
+    }
+
+    /// Consumes the wrapper, returning the wrapped regex.
+    pub fn into_inner(self) -> R {
+        self.re
+    }
+}
+
+impl<R: Regex> From<R> for CachedRegex<R> {
+    fn from(re: R) -> Self {
+        Self {
+            captures_len: re.capture_locations().len(),
+            re,
+        }
+    }
+}
+
+impl<R> RegexPattern for CachedRegex<R> {
+    type Error = Error;
+    type Output = Self;
+
+    #[inline(always)]
+    fn try_into_regex(self) -> Result<Self::Output, Self::Error> {
+        Ok(self)
+    }
+}
+
+impl<R: Regex> Regex for CachedRegex<R> {
+    type Haystack<'h> = R::Haystack<'h>;
+    type CaptureLocations = R::CaptureLocations;
+
+    #[inline]
+    fn capture_locations(&self) -> Self::CaptureLocations {
+        self.re.capture_locations()
+    }
+
+    #[inline]
+    fn as_str(&self) -> &str {
+        self.re.as_str()
+    }
+
+    #[inline]
+    fn capture_names(&self) -> impl Iterator<Item = Option<&str>> {
+        self.re.capture_names()
+    }
+
+    #[inline]
+    fn captures_read(
+        &self,
+        locs: &mut Self::CaptureLocations,
+        haystack: Self::Haystack<'_>,
+    ) -> Option<(usize, usize)> {
+        self.re.captures_read(locs, haystack)
+    }
+
+    #[inline]
+    fn captures_read_at(
+        &self,
+        locs: &mut Self::CaptureLocations,
+        haystack: Self::Haystack<'_>,
+        at: usize,
+    ) -> Option<(usize, usize)> {
+        self.re.captures_read_at(locs, haystack, at)
+    }
+
+    #[inline]
+    fn find(&self, haystack: Self::Haystack<'_>) -> Option<(usize, usize)> {
+        self.re.find(haystack)
+    }
+
+    #[inline]
+    fn is_match_at(&self, haystack: Self::Haystack<'_>, at: usize) -> bool {
+        self.re.is_match_at(haystack, at)
+    }
 }